@@ -0,0 +1,346 @@
+use std::collections::HashSet;
+
+use bevy::{input::InputSystem, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::ron_parsers::Settings;
+
+pub struct ActionsPlugin;
+
+/// Describes what the player wants to do.
+/// Gameplay systems read `Input<Action>` instead of raw keys
+///  that's why keys may be rebound in settings
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Jump,
+    Attack,
+    Interact,
+    Inventory,
+    Pause,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuSelect,
+    MenuBack,
+}
+
+impl Action {
+    /// All actions which are updated every frame
+    pub const ALL: [Self; 15] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::Jump,
+        Self::Attack,
+        Self::Interact,
+        Self::Inventory,
+        Self::Pause,
+        Self::MenuUp,
+        Self::MenuDown,
+        Self::MenuLeft,
+        Self::MenuRight,
+        Self::MenuSelect,
+        Self::MenuBack,
+    ];
+
+    /// Gameplay actions in the order in which they are shown in settings.
+    /// Menu actions are taken only from the settings file
+    ///  to not lose the ability to navigate the menu
+    pub const REBINDABLE: [Self; 9] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::Jump,
+        Self::Attack,
+        Self::Interact,
+        Self::Inventory,
+        Self::Pause,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move left",
+            Self::MoveRight => "Move right",
+            Self::MoveUp => "Move up",
+            Self::MoveDown => "Move down",
+            Self::Jump => "Jump",
+            Self::Attack => "Attack",
+            Self::Interact => "Interact",
+            Self::Inventory => "Inventory",
+            Self::Pause => "Pause",
+            Self::MenuUp => "Menu up",
+            Self::MenuDown => "Menu down",
+            Self::MenuLeft => "Menu left",
+            Self::MenuRight => "Menu right",
+            Self::MenuSelect => "Menu select",
+            Self::MenuBack => "Menu back",
+        }
+    }
+}
+
+/// Exists while the player chooses a new key for the action.
+/// Actions are not triggered meanwhile
+pub struct ActionRebinding(pub Action);
+
+/// State of connected gamepads which is collected from `GamepadEvent`s.
+/// All gamepads control the same player
+#[derive(Default, Debug)]
+pub struct GamepadState {
+    pub buttons: HashSet<GamepadButtonType>,
+    pub left_stick: Vec2,
+}
+
+/// Stick tilt which is ignored to avoid drifting of worn out sticks
+const STICK_DEADZONE: f32 = 0.2;
+
+/// Gamepad button value from which the button is pressed
+const BUTTON_PRESS_THRESHOLD: f32 = 0.5;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Input<Action>>()
+            .init_resource::<Axis<Action>>()
+            .init_resource::<GamepadState>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                track_gamepads.label("track_gamepads").after(InputSystem),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_actions
+                    .label("update_actions")
+                    .after(InputSystem)
+                    .after("track_gamepads"),
+            );
+    }
+}
+
+/// Returns how strong the action is pressed from 0.0 to 1.0.
+/// Keys and buttons are always pressed completely
+///  while sticks may be tilted partially
+pub fn action_strength(actions: &Input<Action>, axes: &Axis<Action>, action: Action) -> f32 {
+    if !actions.pressed(action) {
+        return 0.0;
+    }
+
+    axes.get(action).unwrap_or(1.0)
+}
+
+/// Returns the name of the key which is shown to the player
+pub fn key_name(key: Option<KeyCode>) -> String {
+    key.map_or_else(|| String::from("-"), |key| format!("{:?}", key))
+}
+
+/// Ignores small tilts and stretches the rest of the range into 0.0..1.0
+fn apply_deadzone(value: f32) -> f32 {
+    if value.abs() < STICK_DEADZONE {
+        return 0.0;
+    }
+
+    value.signum() * (value.abs() - STICK_DEADZONE) / (1.0 - STICK_DEADZONE)
+}
+
+/// Returns how much the stick is tilted into the direction of the action
+fn stick_strength(action: Action, stick: Vec2) -> f32 {
+    let value = match action {
+        Action::MoveLeft | Action::MenuLeft => -stick.x,
+        Action::MoveRight | Action::MenuRight => stick.x,
+        Action::MoveUp | Action::MenuUp => stick.y,
+        Action::MoveDown | Action::MenuDown => -stick.y,
+        _ => 0.0,
+    };
+
+    apply_deadzone(value.max(0.0))
+}
+
+fn track_gamepads(
+    mut gamepad_events: EventReader<GamepadEvent>,
+    mut gamepad_state: ResMut<GamepadState>,
+) {
+    for event in gamepad_events.iter() {
+        match event.event_type {
+            GamepadEventType::Disconnected => {
+                *gamepad_state = GamepadState::default();
+            }
+            GamepadEventType::ButtonChanged(button_type, value) => {
+                if value >= BUTTON_PRESS_THRESHOLD {
+                    gamepad_state.buttons.insert(button_type);
+                } else {
+                    gamepad_state.buttons.remove(&button_type);
+                }
+            }
+            GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, value) => {
+                gamepad_state.left_stick.x = value;
+            }
+            GamepadEventType::AxisChanged(GamepadAxisType::LeftStickY, value) => {
+                gamepad_state.left_stick.y = value;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Converts pressed keys and gamepad buttons into actions by bindings from settings
+fn update_actions(
+    keyboard: Res<Input<KeyCode>>,
+    gamepad_state: Res<GamepadState>,
+    settings: Res<Settings>,
+    rebinding: Option<Res<ActionRebinding>>,
+    mut actions: ResMut<Input<Action>>,
+    mut axes: ResMut<Axis<Action>>,
+) {
+    actions.clear();
+
+    for action in Action::ALL {
+        let key_pressed = settings
+            .controls
+            .key(action)
+            .map_or(false, |key| keyboard.pressed(key));
+
+        let button_pressed = settings
+            .controls
+            .gamepad_button(action)
+            .map_or(false, |button| gamepad_state.buttons.contains(&button));
+
+        let strength = if key_pressed || button_pressed {
+            1.0
+        } else {
+            stick_strength(action, gamepad_state.left_stick)
+        };
+
+        if rebinding.is_none() && strength > 0.0 {
+            actions.press(action);
+            axes.set(action, strength);
+        } else {
+            actions.release(action);
+            axes.set(action, 0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod actions_tests {
+    use bevy::ecs::event::Events;
+    use bevy::prelude::*;
+
+    use crate::tests::settings::prepare_settings;
+
+    use super::{action_strength, Action, ActionsPlugin};
+
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        app.insert_resource(prepare_settings())
+            .insert_resource(Input::<KeyCode>::default())
+            .add_event::<GamepadEvent>()
+            .add_plugin(ActionsPlugin);
+
+        app
+    }
+
+    fn send_gamepad_event(app: &mut App, event_type: GamepadEventType) {
+        app.world
+            .resource_mut::<Events<GamepadEvent>>()
+            .send(GamepadEvent::new(Gamepad::new(0), event_type));
+    }
+
+    fn strength(app: &App, action: Action) -> f32 {
+        action_strength(
+            app.world.resource::<Input<Action>>(),
+            app.world.resource::<Axis<Action>>(),
+            action,
+        )
+    }
+
+    #[test]
+    fn should_press_actions_by_gamepad_buttons() {
+        let mut app = prepare_app();
+
+        send_gamepad_event(
+            &mut app,
+            GamepadEventType::ButtonChanged(GamepadButtonType::South, 1.0),
+        );
+        app.update();
+
+        let actions = app.world.resource::<Input<Action>>();
+
+        assert!(actions.just_pressed(Action::Jump));
+        assert!(!actions.pressed(Action::Attack));
+
+        send_gamepad_event(
+            &mut app,
+            GamepadEventType::ButtonChanged(GamepadButtonType::South, 0.0),
+        );
+        app.update();
+
+        let actions = app.world.resource::<Input<Action>>();
+
+        assert!(actions.just_released(Action::Jump));
+    }
+
+    #[test]
+    fn should_ignore_stick_tilt_inside_deadzone() {
+        let mut app = prepare_app();
+
+        send_gamepad_event(
+            &mut app,
+            GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, 0.1),
+        );
+        app.update();
+
+        assert!(!app
+            .world
+            .resource::<Input<Action>>()
+            .pressed(Action::MoveRight));
+        assert_eq!(strength(&app, Action::MoveRight), 0.0);
+    }
+
+    #[test]
+    fn should_move_partially_by_tilted_stick() {
+        let mut app = prepare_app();
+
+        send_gamepad_event(
+            &mut app,
+            GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, -0.6),
+        );
+        app.update();
+
+        let move_left = strength(&app, Action::MoveLeft);
+
+        assert!(move_left > 0.0 && move_left < 1.0);
+        assert_eq!(strength(&app, Action::MoveRight), 0.0);
+
+        send_gamepad_event(
+            &mut app,
+            GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, -1.0),
+        );
+        app.update();
+
+        assert_eq!(strength(&app, Action::MoveLeft), 1.0);
+    }
+
+    #[test]
+    fn should_release_actions_when_gamepad_is_disconnected() {
+        let mut app = prepare_app();
+
+        send_gamepad_event(
+            &mut app,
+            GamepadEventType::ButtonChanged(GamepadButtonType::Start, 1.0),
+        );
+        app.update();
+
+        assert!(app.world.resource::<Input<Action>>().pressed(Action::Pause));
+
+        send_gamepad_event(&mut app, GamepadEventType::Disconnected);
+        app.update();
+
+        assert!(!app.world.resource::<Input<Action>>().pressed(Action::Pause));
+    }
+}
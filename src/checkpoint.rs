@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    common::Health,
+    player::{DeadPlayer, Player, PlayerAnimationState},
+    ApplicationState,
+};
+
+pub struct CheckpointPlugin;
+
+/// Describes a place where the player appears after death
+#[derive(Component, Default, Debug)]
+pub struct Checkpoint {
+    /// Only the last touched checkpoint is active
+    pub active: bool,
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct CheckpointBundle {
+    pub checkpoint: Checkpoint,
+
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+}
+
+/// Describes where the player will be respawned.
+/// The player start position is used until any checkpoint is reached
+pub struct ActiveCheckpoint {
+    pub level: LevelSelection,
+
+    /// World coordinates of the checkpoint
+    pub translation: Vec3,
+}
+
+/// Should be fired when the dead player has to appear at the active checkpoint
+pub struct RespawnPlayerEvent;
+
+const INACTIVE_CHECKPOINT_COLOR: Color = Color::rgba(0.37, 0.84, 0.42, 0.3);
+const ACTIVE_CHECKPOINT_COLOR: Color = Color::rgba(0.37, 0.84, 0.42, 0.8);
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Game)
+                .with_system(spawn_checkpoint)
+                .with_system(remember_player_start)
+                .with_system(activate_checkpoint)
+                .with_system(update_checkpoint_sprite)
+                .into(),
+        )
+        // The player is respawned from the dead menu
+        .add_system(respawn_player)
+        .add_event::<RespawnPlayerEvent>()
+        // Use the same name as it's covered in "LdtkMap"
+        .register_ldtk_entity::<CheckpointBundle>("Checkpoint");
+    }
+}
+
+fn spawn_checkpoint(
+    mut commands: Commands,
+    checkpoint_query: Query<(Entity, &Transform, &EntityInstance), Added<Checkpoint>>,
+) {
+    for (checkpoint_entity, transform, entity_instance) in checkpoint_query.iter() {
+        let width = entity_instance.width as f32;
+        let height = entity_instance.height as f32;
+
+        commands
+            .entity(checkpoint_entity)
+            .insert(Sensor)
+            .insert(Collider::cuboid(width / 2.0, height / 2.0))
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: INACTIVE_CHECKPOINT_COLOR,
+                    custom_size: Some(Vec2::new(width, height)),
+                    ..Default::default()
+                },
+                transform: *transform,
+                ..Default::default()
+            });
+    }
+}
+
+/// The first time the player appears the start position becomes the checkpoint
+fn remember_player_start(
+    mut commands: Commands,
+    active_checkpoint: Option<Res<ActiveCheckpoint>>,
+    level_selection: Res<LevelSelection>,
+    player_query: Query<&Transform, Added<Player>>,
+) {
+    if active_checkpoint.is_some() {
+        return;
+    }
+
+    if let Ok(player_transform) = player_query.get_single() {
+        commands.insert_resource(ActiveCheckpoint {
+            level: level_selection.clone(),
+            translation: player_transform.translation,
+        });
+    }
+}
+
+/// Activates the checkpoint when the player touches it
+fn activate_checkpoint(
+    mut commands: Commands,
+    level_selection: Res<LevelSelection>,
+    mut collisions: EventReader<CollisionEvent>,
+    mut checkpoint_query: Query<(Entity, &mut Checkpoint, &GlobalTransform)>,
+    player_query: Query<With<Player>>,
+) {
+    for collision in collisions.iter() {
+        if let CollisionEvent::Started(collider_a, collider_b, _) = collision {
+            let checkpoint_entity = if checkpoint_query.get(*collider_a).is_ok()
+                && player_query.get(*collider_b).is_ok()
+            {
+                *collider_a
+            } else if checkpoint_query.get(*collider_b).is_ok()
+                && player_query.get(*collider_a).is_ok()
+            {
+                *collider_b
+            } else {
+                continue;
+            };
+
+            for (entity, mut checkpoint, checkpoint_transform) in checkpoint_query.iter_mut() {
+                let active = entity == checkpoint_entity;
+
+                if checkpoint.active != active {
+                    checkpoint.active = active;
+                }
+
+                if active {
+                    commands.insert_resource(ActiveCheckpoint {
+                        level: level_selection.clone(),
+                        translation: checkpoint_transform.translation(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn update_checkpoint_sprite(
+    mut checkpoint_query: Query<(&Checkpoint, &mut Sprite), Changed<Checkpoint>>,
+) {
+    for (checkpoint, mut sprite) in checkpoint_query.iter_mut() {
+        sprite.color = if checkpoint.active {
+            ACTIVE_CHECKPOINT_COLOR
+        } else {
+            INACTIVE_CHECKPOINT_COLOR
+        };
+    }
+}
+
+/// Re-creates the dead player at the active checkpoint
+fn respawn_player(
+    mut commands: Commands,
+    mut respawn_player_event: EventReader<RespawnPlayerEvent>,
+    active_checkpoint: Option<Res<ActiveCheckpoint>>,
+    dead_player: Option<Res<DeadPlayer>>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    world_query: Query<Entity, With<Handle<LdtkAsset>>>,
+) {
+    if respawn_player_event.iter().next().is_none() {
+        return;
+    }
+
+    let (active_checkpoint, dead_player) = match (active_checkpoint, dead_player) {
+        (Some(active_checkpoint), Some(dead_player)) => (active_checkpoint, dead_player),
+        _ => return,
+    };
+
+    *level_selection = active_checkpoint.level.clone();
+
+    let player_entity =
+        spawn_dead_player(&mut commands, &dead_player, active_checkpoint.translation);
+
+    // Worldly entities are placed inside the world
+    if let Ok(world_entity) = world_query.get_single() {
+        commands.entity(world_entity).add_child(player_entity);
+    }
+
+    commands.remove_resource::<DeadPlayer>();
+    commands.insert_resource(NextState(PlayerAnimationState::Idle));
+    commands.insert_resource(NextState(ApplicationState::Game));
+
+    // Turn on the physics when we resume the game
+    rapier_config.physics_pipeline_active = true;
+}
+
+/// Re-creates the dead player at the specific place.
+/// All other components are inserted by `spawn_player` system
+///  as for the player which has been spawned by LDtk
+pub fn spawn_dead_player(
+    commands: &mut Commands,
+    dead_player: &DeadPlayer,
+    translation: Vec3,
+) -> Entity {
+    commands
+        .spawn()
+        .insert(Player)
+        // `spawn_player` system sets the full health
+        .insert(Health { current: 1, max: 1 })
+        // Keep the same LDtk data to avoid spawning one more player
+        //  when the player level is loaded again
+        .insert(dead_player.worldly.clone())
+        .insert(dead_player.entity_instance.clone())
+        .insert(dead_player.inventory.clone())
+        .insert_bundle(TransformBundle::from_transform(
+            Transform::from_translation(translation),
+        ))
+        .id()
+}
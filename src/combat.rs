@@ -1,212 +1,1079 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use iyes_loopless::prelude::*;
 
-use crate::common::{Attackable, Attacks, Health};
+use crate::common::{
+    Attackable, Attacks, Damage, Health, Invulnerable, Knockback, MovementDirection,
+};
 use crate::{
+    actions::Action,
     enemy::Enemy,
-    player::{Player, SideDetector, SideSensor},
-    ApplicationState, PlayerIsHitEvent,
+    fixed_timestep::{FixedTimestepAppExt, FIXED_TIMESTEP, FIXED_UPDATE, GAMEPLAY_STAGE},
+    items::Equipment,
+    player::{GroundSensor, JumpState, Player},
+    ron_parsers::CombatConfig,
+    ApplicationState, DamageEvent,
 };
 
 pub struct CombatPlugin;
 
+/// Duration of one frame of the attack sprite sheet
+const ATTACK_FRAME_DURATION: Duration = Duration::from_millis(40);
+
+/// Time after the recovery until the next swing
+const ATTACK_COOLDOWN: Duration = Duration::from_millis(150);
+
+/// Collision group of enemies. The invulnerable player
+///  may pass through them
+pub const ENEMY_COLLISION_GROUP: u32 = 1 << 1;
+
+/// How often the invulnerable entity blinks (in seconds)
+const INVULNERABLE_BLINK_INTERVAL: f32 = 0.1;
+
+/// Sprite transparency when the blinking entity is hidden
+const INVULNERABLE_BLINK_ALPHA: f32 = 0.2;
+
+/// Describes in which part of the swing the attacker is
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AttackPhase {
+    /// The attacker swings but doesn't hurt yet
+    Startup,
+
+    /// The hitbox hurts everything inside
+    Active,
+
+    /// The attacker returns to the normal pose
+    Recovery,
+}
+
+/// Sensor in front of the attacker which hurts on active frames
+#[derive(Component)]
+pub struct AttackHitbox {
+    pub attacker: Entity,
+}
+
+impl Attacks {
+    pub fn is_attacking(&self) -> bool {
+        self.swing.is_some()
+    }
+
+    /// Returns the frame of the attack sprite sheet
+    pub fn frame(&self) -> Option<usize> {
+        self.swing
+            .map(|swing| (swing.as_nanos() / ATTACK_FRAME_DURATION.as_nanos()) as usize)
+    }
+
+    pub fn phase(&self) -> Option<AttackPhase> {
+        self.frame().map(|frame| {
+            if frame < self.frames.startup {
+                AttackPhase::Startup
+            } else if frame < self.frames.startup + self.frames.active {
+                AttackPhase::Active
+            } else {
+                AttackPhase::Recovery
+            }
+        })
+    }
+
+    /// Interrupts the swing (e.g. when the attacker receives a hit)
+    pub fn cancel(&mut self) {
+        self.swing = None;
+    }
+}
+
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
+        // Collision events are read every frame because they are
+        //  cleared after two frames and steps might be rarer.
+        // Knockback impulses are applied on the next physics step
         app.add_system_set(
             ConditionSet::new()
                 .run_in_state(ApplicationState::Game)
+                .with_system(setup_player_combat)
+                .with_system(stomp_detection)
                 .with_system(combat_interaction_detection)
-                .with_system(player_receives_damage)
-                .with_system(player_attacks)
-                .with_system(attack_detection)
+                .with_system(apply_damage)
+                .with_system(update_invulnerability)
+                .with_system(spawn_attack_hitbox)
                 .into(),
+        )
+        // Swings are measured in steps to have the same timings with any frame rate
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            advance_attacks
+                .run_in_state(ApplicationState::Game)
+                .label("advance_attacks"),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            player_attacks
+                .run_in_state(ApplicationState::Game)
+                .after("advance_attacks"),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            place_attack_hitbox.run_in_state(ApplicationState::Game),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            attack_detection
+                .run_in_state(ApplicationState::Game)
+                .after("advance_attacks"),
         );
     }
 }
 
+/// The player hits as it's described in the combat config
+fn setup_player_combat(
+    mut commands: Commands,
+    combat_config: Res<CombatConfig>,
+    player_query: Query<Entity, Added<Player>>,
+) {
+    for player_entity in player_query.iter() {
+        commands
+            .entity(player_entity)
+            .insert(Damage(combat_config.player.damage))
+            .insert(Knockback(combat_config.player.knockback));
+    }
+}
+
+/// Spawns a hitbox in front of every entity which may attack
+fn spawn_attack_hitbox(
+    mut commands: Commands,
+    attackers_query: Query<(Entity, &Collider), Added<Attacks>>,
+) {
+    for (entity, collider) in attackers_query.iter() {
+        // Bounding box is used to place the hitbox next to any collider shape
+        let aabb = collider.raw.compute_local_aabb();
+        let half_extents = Vec2::new(aabb.half_extents().x, aabb.half_extents().y);
+        let center = Vec2::new(aabb.center().x, aabb.center().y);
+
+        // The hitbox is as wide as a half of the attacker
+        //  and starts right from its side
+        let hitbox_half_width = half_extents.x / 2.0;
+
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn()
+                .insert(Sensor)
+                .insert(Collider::cuboid(hitbox_half_width, half_extents.y))
+                .insert(ColliderMassProperties::Density(0.0))
+                .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                    center.x + half_extents.x + hitbox_half_width,
+                    center.y,
+                    0.0,
+                )))
+                .insert(AttackHitbox { attacker: entity });
+        });
+    }
+}
+
+/// Keeps the hitbox on the side where the attacker looks
+fn place_attack_hitbox(
+    attackers_query: Query<&MovementDirection, With<Attacks>>,
+    mut hitboxes_query: Query<(&AttackHitbox, &mut Transform)>,
+) {
+    for (hitbox, mut transform) in hitboxes_query.iter_mut() {
+        if let Ok(direction) = attackers_query.get(hitbox.attacker) {
+            let offset = transform.translation.x.abs();
+            let x = match direction {
+                MovementDirection::Right => offset,
+                MovementDirection::Left => -offset,
+            };
+
+            if transform.translation.x != x {
+                transform.translation.x = x;
+            }
+        }
+    }
+}
+
+/// Moves swings forward and finishes them after the last frame
+fn advance_attacks(mut attacks_query: Query<&mut Attacks>) {
+    for mut attacks in attacks_query.iter_mut() {
+        let swing_duration = ATTACK_FRAME_DURATION * attacks.frames.total() as u32;
+
+        if !attacks.cooldown.is_zero() {
+            attacks.cooldown = attacks.cooldown.saturating_sub(FIXED_TIMESTEP);
+        }
+
+        if let Some(swing) = attacks.swing {
+            let swing = swing + FIXED_TIMESTEP;
+
+            if swing >= swing_duration {
+                attacks.swing = None;
+                attacks.cooldown = ATTACK_COOLDOWN;
+            } else {
+                attacks.swing = Some(swing);
+            }
+        }
+    }
+}
+
+/// Hurts everything inside hitboxes on active frames.
+/// Every entity is hit only once per swing
 fn attack_detection(
-    side_detectors: Query<(&Attacks, &GlobalTransform), With<SideDetector>>,
-    side_sensors: Query<(Entity, &SideSensor)>,
+    rapier_context: Res<RapierContext>,
+    mut attackers_query: Query<(
+        &mut Attacks,
+        &Damage,
+        &Knockback,
+        &GlobalTransform,
+        Option<&Equipment>,
+    )>,
+    hitboxes_query: Query<(Entity, &AttackHitbox)>,
+    attackable_query: Query<&GlobalTransform, With<Attackable>>,
+    mut damage_event: EventWriter<DamageEvent>,
+) {
+    for (hitbox_entity, hitbox) in hitboxes_query.iter() {
+        let (mut attacks, damage, knockback, attacker_transform, attacker_equipment) =
+            match attackers_query.get_mut(hitbox.attacker) {
+                Ok(attacker) => attacker,
+                Err(_) => continue,
+            };
+
+        if attacks.phase() != Some(AttackPhase::Active) {
+            continue;
+        }
+
+        for (collider_a, collider_b, intersecting) in
+            rapier_context.intersections_with(hitbox_entity)
+        {
+            let target = if collider_a == hitbox_entity {
+                collider_b
+            } else {
+                collider_a
+            };
+
+            if !intersecting || attacks.hit_entities.contains(&target) {
+                continue;
+            }
+
+            if let Ok(attackable_transform) = attackable_query.get(target) {
+                attacks.hit_entities.insert(target);
+
+                damage_event.send(DamageEvent {
+                    source: hitbox.attacker,
+                    target,
+                    // Equipped weapon increases the damage
+                    amount: damage.0
+                        + attacker_equipment.map_or(0, |equipment| equipment.modifiers.damage),
+                    knockback: knockback.towards(
+                        attacker_transform.translation(),
+                        attackable_transform.translation(),
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Returns the pair of colliders where the first one matches the predicate
+pub(crate) fn find_pair(
+    collider_a: Entity,
+    collider_b: Entity,
+    predicate: impl Fn(Entity) -> bool,
+) -> Option<(Entity, Entity)> {
+    if predicate(collider_a) {
+        Some((collider_a, collider_b))
+    } else if predicate(collider_b) {
+        Some((collider_b, collider_a))
+    } else {
+        None
+    }
+}
+
+/// The player stomps the enemy when the ground sensor of the player
+///  touches the enemy. The enemy is hurt and the player bounces off it
+#[allow(clippy::type_complexity)]
+fn stomp_detection(
     mut collisions: EventReader<CollisionEvent>,
-    mut attackable_query: Query<
-        (&mut Health, &mut ExternalImpulse, &GlobalTransform),
-        With<Attackable>,
+    combat_config: Res<CombatConfig>,
+    ground_sensors_query: Query<&GroundSensor>,
+    mut player_query: Query<
+        (
+            &mut ExternalImpulse,
+            &mut Velocity,
+            &mut JumpState,
+            &GlobalTransform,
+            &Damage,
+            &Knockback,
+        ),
+        (With<Player>, Without<Enemy>),
     >,
+    enemy_query: Query<(&Health, &GlobalTransform), (With<Enemy>, Without<Player>)>,
+    mut damage_event: EventWriter<DamageEvent>,
 ) {
-    for (side_sensor_entity, side_sensor) in side_sensors.iter() {
-        for collision in collisions.iter() {
-            match collision {
-                CollisionEvent::Started(collision_a, collision_b, _) => {
-                    let offset_x = 50.0;
-                    let offset_y = 30.0;
-
-                    let attacker = if let Ok(pl1) = side_detectors.get(side_sensor.detection_entity)
-                    {
-                        Some(pl1)
-                    } else {
-                        None
-                    };
-
-                    let attackable = if *collision_b == side_sensor_entity {
-                        if let Ok(x) = attackable_query.get_mut(*collision_a) {
-                            Some(x)
-                        } else {
-                            None
-                        }
-                    } else if *collision_a == side_sensor_entity {
-                        if let Ok(x) = attackable_query.get_mut(*collision_b) {
-                            Some(x)
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    };
-
-                    if let Some((attacks, attacker_transform)) = attacker {
-                        if let Some((
-                            mut attackable_health,
-                            mut attackable_impulse,
-                            attackable_transform,
-                        )) = attackable
-                        {
-                            if attacks.0 {
-                                attackable_health.current -= 1;
-
-                                // Give an impulse to the left or right depending on
-                                //  where is the attacker and where is an attackable entity
-                                if attacker_transform.translation().x
-                                    < attackable_transform.translation().x
-                                {
-                                    attackable_impulse.impulse = Vec2::new(offset_x, offset_y);
-                                } else {
-                                    attackable_impulse.impulse = Vec2::new(-offset_x, offset_y);
-                                }
-                            }
-                        }
-                    }
+    for collision in collisions.iter() {
+        if let CollisionEvent::Started(collider_a, collider_b, _) = collision {
+            let stomp = find_pair(*collider_a, *collider_b, |entity| {
+                ground_sensors_query.get(entity).is_ok()
+            });
+
+            let (sensor_entity, enemy_entity) = match stomp {
+                Some(stomp) => stomp,
+                None => continue,
+            };
+
+            let player_entity = ground_sensors_query
+                .get(sensor_entity)
+                .expect("Ground sensor must exist")
+                .detection_entity;
+
+            if let (
+                Ok((
+                    mut player_impulse,
+                    mut player_velocity,
+                    mut jump_state,
+                    player_transform,
+                    player_damage,
+                    player_knockback,
+                )),
+                Ok((enemy_health, enemy_transform)),
+            ) = (
+                player_query.get_mut(player_entity),
+                enemy_query.get(enemy_entity),
+            ) {
+                if enemy_health.current <= 0 {
+                    continue;
                 }
-                CollisionEvent::Stopped(_, _, _) => {}
+
+                damage_event.send(DamageEvent {
+                    source: player_entity,
+                    target: enemy_entity,
+                    amount: player_damage.0,
+                    knockback: player_knockback.towards(
+                        player_transform.translation(),
+                        enemy_transform.translation(),
+                    ),
+                });
+
+                // The falling speed must not eat the bounce
+                player_velocity.linvel.y = 0.0;
+                player_impulse.impulse = Vec2::new(0.0, combat_config.stomp.bounce);
+
+                // The bounce counts as the first jump
+                //  so the player is able to jump once again in the air
+                jump_state.jumps_made = 1;
+                jump_state.can_jump = true;
             }
         }
     }
 }
 
+/// The enemy hurts the player when they touch each other.
+/// Touches from above are stomps and handled by `stomp_detection`
 #[allow(clippy::type_complexity)]
 fn combat_interaction_detection(
     mut collisions: EventReader<CollisionEvent>,
-    mut player_query: Query<
-        (&mut ExternalImpulse, &Collider, &GlobalTransform),
-        (With<Player>, Without<Enemy>),
-    >,
+    ground_sensors_query: Query<&GroundSensor>,
+    player_query: Query<(Entity, &GlobalTransform), (With<Player>, Without<Enemy>)>,
     mut enemy_query: Query<
+        (&mut ExternalImpulse, &GlobalTransform, &Damage, &Knockback),
+        (With<Enemy>, Without<Player>),
+    >,
+    mut damage_event: EventWriter<DamageEvent>,
+) {
+    let started_collisions: Vec<(Entity, Entity)> = collisions
+        .iter()
+        .filter_map(|collision| match collision {
+            CollisionEvent::Started(collider_a, collider_b, _) => Some((*collider_a, *collider_b)),
+            CollisionEvent::Stopped(_, _, _) => None,
+        })
+        .collect();
+
+    // The ground sensor is lower than the player collider
+    //  so the stomp starts before or together with the touch
+    let stomped_enemies: HashSet<(Entity, Entity)> = started_collisions
+        .iter()
+        .filter_map(|(collider_a, collider_b)| {
+            find_pair(*collider_a, *collider_b, |entity| {
+                ground_sensors_query.get(entity).is_ok()
+            })
+        })
+        .filter_map(|(sensor_entity, enemy_entity)| {
+            ground_sensors_query
+                .get(sensor_entity)
+                .ok()
+                .map(|sensor| (sensor.detection_entity, enemy_entity))
+        })
+        .chain(ground_sensors_query.iter().flat_map(|sensor| {
+            sensor
+                .intersecting_entities
+                .iter()
+                .map(|entity| (sensor.detection_entity, *entity))
+        }))
+        .collect();
+
+    for (collider_a, collider_b) in started_collisions {
+        let touch = find_pair(collider_a, collider_b, |entity| {
+            player_query.get(entity).is_ok()
+        });
+
+        let (player_entity, enemy_entity) = match touch {
+            Some(touch) => touch,
+            None => continue,
+        };
+
+        if stomped_enemies.contains(&(player_entity, enemy_entity)) {
+            continue;
+        }
+
+        if let (
+            Ok((_, player_transform)),
+            Ok((mut enemy_impulse, enemy_transform, enemy_damage, enemy_knockback)),
+        ) = (
+            player_query.get(player_entity),
+            enemy_query.get_mut(enemy_entity),
+        ) {
+            // The player and the enemy bounce off each other
+            let player_knockback = enemy_knockback.towards(
+                enemy_transform.translation(),
+                player_transform.translation(),
+            );
+
+            damage_event.send(DamageEvent {
+                source: enemy_entity,
+                target: player_entity,
+                amount: enemy_damage.0,
+                knockback: player_knockback,
+            });
+
+            enemy_impulse.impulse = Vec2::new(-player_knockback.x, player_knockback.y);
+        }
+    }
+}
+
+/// Applies damage and knockback to any `Attackable` entity.
+/// The player becomes invulnerable for a while after every hit
+#[allow(clippy::type_complexity)]
+fn apply_damage(
+    mut commands: Commands,
+    combat_config: Res<CombatConfig>,
+    mut damage_event: EventReader<DamageEvent>,
+    mut attackable_query: Query<
         (
             &mut Health,
             &mut ExternalImpulse,
-            &Collider,
-            &GlobalTransform,
+            Option<&Equipment>,
+            Option<&Invulnerable>,
+            Option<&Player>,
         ),
-        (With<Enemy>, Without<Player>),
+        With<Attackable>,
     >,
-    mut hit_the_player_event: EventWriter<PlayerIsHitEvent>,
 ) {
-    for collision in collisions.iter() {
-        // Now impulse by `x` axis looks so sharp. I have to understand how to make it
-        //  smooth and only after that it would be possible to increase this value
-        //  up to 100 or 300 depends on smoothness
-        let impulse_force_horizontal = 120.0;
-        let impulse_force_vertical = 30.0;
-
-        match collision {
-            CollisionEvent::Started(collider_a, collider_b, _) => {
-                let player = if let Ok(pl1) = player_query.get_mut(*collider_a) {
-                    Some(pl1)
-                } else if let Ok(pl2) = player_query.get_mut(*collider_b) {
-                    Some(pl2)
-                } else {
-                    None
-                };
-
-                let enemy = if let Ok(en1) = enemy_query.get_mut(*collider_b) {
-                    Some(en1)
-                } else if let Ok(en2) = enemy_query.get_mut(*collider_a) {
-                    Some(en2)
-                } else {
-                    None
-                };
-
-                if let Some((mut player_impulse, player_collider, player_transform)) = player {
-                    if let Some((
-                        mut enemy_health,
-                        mut enemy_impulse,
-                        enemy_collider,
-                        enemy_transform,
-                    )) = enemy
-                    {
-                        let player_half_size = player_collider
-                            .as_cuboid()
-                            .expect("Player collider must be cuboid")
-                            .half_extents();
-
-                        let enemy_half_size = enemy_collider
-                            .as_cuboid()
-                            .expect("Enemy collider must be cuboid")
-                            .half_extents();
-
-                        if ((player_transform.translation().y - player_half_size.y)
-                            - (enemy_transform.translation().y + enemy_half_size.y))
-                            < -3.0
-                        {
-                            hit_the_player_event.send(PlayerIsHitEvent(1));
-                        } else if enemy_health.current > 0 {
-                            enemy_health.current -= 1;
-                        }
-
-                        // We should push player on left - otherwise - on right
-                        if player_transform.translation().x < enemy_transform.translation().x {
-                            player_impulse.impulse =
-                                Vec2::new(-impulse_force_horizontal, impulse_force_vertical);
-                            enemy_impulse.impulse =
-                                Vec2::new(impulse_force_horizontal, impulse_force_vertical);
-                        } else {
-                            player_impulse.impulse =
-                                Vec2::new(impulse_force_horizontal, impulse_force_vertical);
-                            enemy_impulse.impulse =
-                                Vec2::new(-impulse_force_horizontal, impulse_force_vertical);
-                        }
-                    }
+    // `Invulnerable` component is inserted only at the end of the stage
+    //  so several hits at the same frame are filtered here
+    let mut hit_players = HashSet::new();
+
+    for damage in damage_event.iter() {
+        if let Ok((mut health, mut impulse, equipment, invulnerable, player)) =
+            attackable_query.get_mut(damage.target)
+        {
+            if health.current <= 0 || invulnerable.is_some() || hit_players.contains(&damage.target)
+            {
+                continue;
+            }
+
+            // Armor reduces the damage but the target always loses at least 1 point
+            let defense = equipment.map_or(0, |equipment| equipment.modifiers.defense);
+
+            health.current -= (damage.amount - defense).max(1);
+            impulse.impulse = damage.knockback;
+
+            if player.is_some() {
+                hit_players.insert(damage.target);
+
+                let invulnerability = &combat_config.invulnerability;
+                let mut target_commands = commands.entity(damage.target);
+
+                target_commands.insert(Invulnerable {
+                    timer: Timer::from_seconds(invulnerability.duration, false),
+                });
+
+                if invulnerability.pass_through_enemies {
+                    target_commands.insert(CollisionGroups::new(u32::MAX, !ENEMY_COLLISION_GROUP));
                 }
             }
-            CollisionEvent::Stopped(_, _, _) => {}
         }
     }
 }
 
-fn player_receives_damage(
-    mut player_query: Query<&mut Health, With<Player>>,
-    mut hit_the_player_event: EventReader<PlayerIsHitEvent>,
+/// Blinks the sprite of the invulnerable entity
+///  and makes it vulnerable again when the time is over
+fn update_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut invulnerable_query: Query<(Entity, &mut Invulnerable, &mut TextureAtlasSprite)>,
 ) {
-    for damage in hit_the_player_event.iter() {
-        if let Ok(mut player_health) = player_query.get_single_mut() {
-            if player_health.current > 0 {
-                player_health.current -= damage.0;
-            }
+    for (entity, mut invulnerable, mut sprite) in invulnerable_query.iter_mut() {
+        invulnerable.timer.tick(time.delta());
+
+        if invulnerable.timer.finished() {
+            sprite.color.set_a(1.0);
+
+            commands
+                .entity(entity)
+                .remove::<Invulnerable>()
+                .insert(CollisionGroups::default());
+
+            continue;
         }
+
+        let hidden =
+            (invulnerable.timer.elapsed_secs() / INVULNERABLE_BLINK_INTERVAL) as u32 % 2 == 1;
+
+        sprite.color.set_a(if hidden {
+            INVULNERABLE_BLINK_ALPHA
+        } else {
+            1.0
+        });
     }
 }
 
+/// Starts a new swing. The next swing is allowed
+///  only after the recovery and the cooldown
 fn player_attacks(
     mut player_query: Query<&mut Attacks, With<Player>>,
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
 ) {
-    if keyboard.just_pressed(KeyCode::LShift) {
-        if let Ok(mut attacks) = player_query.get_single_mut() {
-            if attacks.0 {
-                attacks.0 = false;
-            } else {
-                attacks.0 = true;
+    if !actions.just_pressed(Action::Attack) {
+        return;
+    }
+
+    if let Ok(mut attacks) = player_query.get_single_mut() {
+        if attacks.is_attacking() || !attacks.cooldown.is_zero() {
+            return;
+        }
+
+        attacks.swing = Some(Duration::ZERO);
+        attacks.hit_entities.clear();
+    }
+}
+
+#[cfg(test)]
+mod combat_tests {
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    use bevy::asset::AssetPlugin;
+    use bevy::ecs::event::{Events, ManualEventReader};
+    use bevy::hierarchy::HierarchyPlugin;
+    use bevy::prelude::*;
+    use bevy::transform::TransformPlugin;
+    use bevy_rapier2d::prelude::*;
+    use bevy_rapier2d::rapier::geometry::CollisionEventFlags;
+
+    use crate::actions::Action;
+    use crate::common::{
+        Attackable, Attacks, Damage, Health, Invulnerable, Knockback, SwingFrames,
+    };
+    use crate::enemy::Enemy;
+    use crate::fixed_timestep::{FixedTimestepPlugin, ForcedFrameDuration, FIXED_TIMESTEP};
+    use crate::physics::PhysicsPlugin;
+    use crate::player::{GroundSensor, JumpState, Player};
+    use crate::tests::combat::prepare_combat_config;
+    use crate::{DamageEvent, GameMode};
+
+    use super::{
+        advance_attacks, apply_damage, attack_detection, combat_interaction_detection,
+        player_attacks, spawn_attack_hitbox, stomp_detection, update_invulnerability, AttackPhase,
+        ATTACK_COOLDOWN, ENEMY_COLLISION_GROUP,
+    };
+
+    const SWING_FRAMES: SwingFrames = SwingFrames {
+        startup: 3,
+        active: 3,
+        recovery: 2,
+    };
+
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        let mut time = Time::default();
+        let startup = time.startup();
+
+        // The first update only remembers the instant
+        time.update_with_instant(startup);
+
+        app.insert_resource(prepare_combat_config())
+            .insert_resource(time)
+            .add_event::<DamageEvent>()
+            .add_system(apply_damage)
+            .add_system(update_invulnerability);
+
+        app
+    }
+
+    fn spawn_player(app: &mut App) -> Entity {
+        app.world
+            .spawn()
+            .insert(Player)
+            .insert(Attackable)
+            .insert(Health { current: 5, max: 5 })
+            .insert(ExternalImpulse::default())
+            .insert(TextureAtlasSprite::default())
+            .id()
+    }
+
+    fn spawn_enemy(app: &mut App) -> Entity {
+        app.world
+            .spawn()
+            .insert(Enemy)
+            .insert(Attackable)
+            .insert(Health { current: 3, max: 3 })
+            .insert(ExternalImpulse::default())
+            .insert(Damage(1))
+            .insert(Knockback(Vec2::new(100.0, 30.0)))
+            .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                10.0, 0.0, 0.0,
+            )))
+            .id()
+    }
+
+    fn hit(app: &mut App, target: Entity) {
+        let source = app.world.spawn().id();
+
+        app.world
+            .resource_mut::<Events<DamageEvent>>()
+            .send(DamageEvent {
+                source,
+                target,
+                amount: 1,
+                knockback: Vec2::new(100.0, 30.0),
+            });
+    }
+
+    fn advance_time(app: &mut App, duration: Duration) {
+        let mut time = app.world.resource_mut::<Time>();
+        let last_update = time.last_update().unwrap_or_else(|| time.startup());
+
+        time.update_with_instant(last_update + duration);
+    }
+
+    fn health(app: &App, entity: Entity) -> i32 {
+        app.world
+            .get::<Health>(entity)
+            .expect("Player must have a health")
+            .current
+    }
+
+    /// Prepares the app where every update is one step of the swing
+    fn prepare_attacks_app() -> App {
+        let mut app = App::new();
+
+        app.insert_resource(Input::<Action>::default())
+            .add_system(advance_attacks.label("advance_attacks"))
+            .add_system(player_attacks.after("advance_attacks"));
+
+        app
+    }
+
+    /// Prepares the headless app with physics where the hitbox of the attacker
+    ///  overlaps the target. Returns the attacker and the target entities
+    fn prepare_hitbox_app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            // Rapier looks for meshes and scenes of async colliders
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<Scene>()
+            .insert_resource(GameMode::Platformer)
+            .insert_resource(Input::<Action>::default())
+            .insert_resource(ForcedFrameDuration(FIXED_TIMESTEP))
+            .add_event::<DamageEvent>()
+            .add_plugin(FixedTimestepPlugin)
+            .add_plugin(PhysicsPlugin)
+            .add_system(spawn_attack_hitbox)
+            .add_system(advance_attacks.label("advance_attacks"))
+            .add_system(attack_detection.after("advance_attacks"));
+
+        let attacker_id = app
+            .world
+            .spawn()
+            .insert(RigidBody::Dynamic)
+            .insert(GravityScale(0.0))
+            .insert(Collider::cuboid(10.0, 10.0))
+            .insert(Damage(1))
+            .insert(Knockback(Vec2::new(100.0, 30.0)))
+            .insert(Attacks {
+                frames: SWING_FRAMES,
+                ..default()
+            })
+            .insert_bundle(TransformBundle::default())
+            .id();
+
+        // The hitbox is between 10.0 and 20.0 on the right side of the attacker
+        let target_id = app
+            .world
+            .spawn()
+            .insert(RigidBody::Dynamic)
+            .insert(GravityScale(0.0))
+            .insert(Collider::cuboid(4.0, 4.0))
+            .insert(Attackable)
+            .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                16.0, 0.0, 0.0,
+            )))
+            .id();
+
+        (app, attacker_id, target_id)
+    }
+
+    fn attacks(app: &App, entity: Entity) -> &Attacks {
+        app.world
+            .get::<Attacks>(entity)
+            .expect("Attacker must have attacks")
+    }
+
+    fn start_swing(app: &mut App, entity: Entity) {
+        let mut attacks = app
+            .world
+            .get_mut::<Attacks>(entity)
+            .expect("Attacker must have attacks");
+
+        attacks.swing = Some(Duration::ZERO);
+        attacks.hit_entities.clear();
+    }
+
+    fn press_attack(app: &mut App) {
+        app.world
+            .resource_mut::<Input<Action>>()
+            .press(Action::Attack);
+        app.update();
+
+        let mut actions = app.world.resource_mut::<Input<Action>>();
+
+        actions.release(Action::Attack);
+        actions.clear();
+    }
+
+    #[test]
+    fn should_go_through_the_swing_phases_by_frames_of_the_sheet() {
+        let mut app = prepare_attacks_app();
+        let attacker_id = app
+            .world
+            .spawn()
+            .insert(Attacks {
+                frames: SWING_FRAMES,
+                ..default()
+            })
+            .id();
+
+        start_swing(&mut app, attacker_id);
+
+        let mut phases = Vec::new();
+
+        while let Some(phase) = attacks(&app, attacker_id).phase() {
+            let frame = attacks(&app, attacker_id)
+                .frame()
+                .expect("Swing must have a frame");
+            let expected_phase = match frame {
+                0..=2 => AttackPhase::Startup,
+                3..=5 => AttackPhase::Active,
+                _ => AttackPhase::Recovery,
+            };
+
+            assert_eq!(phase, expected_phase);
+
+            phases.push(phase);
+            app.update();
+        }
+
+        assert!(phases.contains(&AttackPhase::Startup));
+        assert!(phases.contains(&AttackPhase::Active));
+        assert_eq!(phases.last(), Some(&AttackPhase::Recovery));
+
+        // The swing lasts as long as the whole sheet
+        let swing_duration = FIXED_TIMESTEP * phases.len() as u32;
+
+        assert!(swing_duration >= super::ATTACK_FRAME_DURATION * SWING_FRAMES.total() as u32);
+        assert_eq!(attacks(&app, attacker_id).cooldown, ATTACK_COOLDOWN);
+    }
+
+    #[test]
+    fn should_not_attack_again_until_the_cooldown_is_over() {
+        let mut app = prepare_attacks_app();
+        let player_id = app
+            .world
+            .spawn()
+            .insert(Player)
+            .insert(Attacks {
+                frames: SWING_FRAMES,
+                ..default()
+            })
+            .id();
+
+        press_attack(&mut app);
+
+        assert!(attacks(&app, player_id).is_attacking());
+
+        // Pressing during the swing doesn't restart it
+        app.update();
+        press_attack(&mut app);
+
+        assert!(attacks(&app, player_id).swing > Some(FIXED_TIMESTEP));
+
+        while attacks(&app, player_id).is_attacking() {
+            app.update();
+        }
+
+        press_attack(&mut app);
+
+        assert!(!attacks(&app, player_id).is_attacking());
+
+        while !attacks(&app, player_id).cooldown.is_zero() {
+            app.update();
+        }
+
+        press_attack(&mut app);
+
+        assert!(attacks(&app, player_id).is_attacking());
+    }
+
+    #[test]
+    fn should_hit_the_target_only_once_per_swing() {
+        let (mut app, attacker_id, target_id) = prepare_hitbox_app();
+        let mut damage_reader = ManualEventReader::<DamageEvent>::default();
+        let mut hit_phases = Vec::new();
+
+        // Let physics find the overlap before the swing
+        app.update();
+        app.update();
+
+        for _ in 0..2 {
+            start_swing(&mut app, attacker_id);
+
+            while attacks(&app, attacker_id).is_attacking() {
+                app.update();
+
+                // The swing is advanced before the detection
+                let phase = attacks(&app, attacker_id).phase();
+                let events = app.world.resource::<Events<DamageEvent>>();
+
+                for damage in damage_reader.iter(events) {
+                    assert_eq!(damage.source, attacker_id);
+                    assert_eq!(damage.target, target_id);
+
+                    hit_phases.push(phase);
+                }
             }
         }
+
+        // Every swing hits once on its active frames
+        assert_eq!(hit_phases, vec![Some(AttackPhase::Active); 2]);
+    }
+
+    #[test]
+    fn should_damage_and_push_the_enemy() {
+        let mut app = prepare_app();
+        let enemy_id = spawn_enemy(&mut app);
+
+        hit(&mut app, enemy_id);
+        app.update();
+
+        assert_eq!(health(&app, enemy_id), 2);
+
+        let impulse = app
+            .world
+            .get::<ExternalImpulse>(enemy_id)
+            .expect("Enemy must have an impulse");
+
+        assert_eq!(impulse.impulse, Vec2::new(100.0, 30.0));
+
+        // Only the player becomes invulnerable after the hit
+        assert!(app.world.get::<Invulnerable>(enemy_id).is_none());
+
+        hit(&mut app, enemy_id);
+        app.update();
+
+        assert_eq!(health(&app, enemy_id), 1);
+    }
+
+    #[test]
+    fn should_stomp_the_enemy_instead_of_the_touch() {
+        let mut app = prepare_app();
+
+        app.add_event::<CollisionEvent>()
+            .add_system(stomp_detection)
+            .add_system(combat_interaction_detection);
+
+        let player_id = spawn_player(&mut app);
+
+        app.world
+            .entity_mut(player_id)
+            .insert(Velocity::linear(Vec2::new(0.0, -200.0)))
+            .insert(JumpState {
+                jumps_made: 2,
+                can_jump: false,
+            })
+            .insert(Damage(1))
+            .insert(Knockback(Vec2::new(100.0, 30.0)))
+            .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                10.0, 20.0, 0.0,
+            )));
+
+        let sensor_id = app
+            .world
+            .spawn()
+            .insert(GroundSensor {
+                detection_entity: player_id,
+                intersecting_entities: HashSet::new(),
+            })
+            .id();
+        let enemy_id = spawn_enemy(&mut app);
+
+        // The ground sensor and the player touch the enemy at the same frame
+        let mut collisions = app.world.resource_mut::<Events<CollisionEvent>>();
+
+        collisions.send(CollisionEvent::Started(
+            sensor_id,
+            enemy_id,
+            CollisionEventFlags::SENSOR,
+        ));
+        collisions.send(CollisionEvent::Started(
+            enemy_id,
+            player_id,
+            CollisionEventFlags::empty(),
+        ));
+
+        // Damage events are applied on the next frame
+        //  if the detection runs after `apply_damage`
+        app.update();
+        app.update();
+
+        assert_eq!(health(&app, enemy_id), 2);
+        assert_eq!(health(&app, player_id), 5);
+        assert!(app.world.get::<Invulnerable>(player_id).is_none());
+
+        let velocity = app
+            .world
+            .get::<Velocity>(player_id)
+            .expect("Player must have a velocity");
+        let impulse = app
+            .world
+            .get::<ExternalImpulse>(player_id)
+            .expect("Player must have an impulse");
+        let jump_state = app
+            .world
+            .get::<JumpState>(player_id)
+            .expect("Player must have a jump state");
+
+        // The bounce of the test config
+        assert_eq!(velocity.linvel.y, 0.0);
+        assert_eq!(impulse.impulse, Vec2::new(0.0, 50.0));
+        assert_eq!(jump_state.jumps_made, 1);
+        assert!(jump_state.can_jump);
+    }
+
+    #[test]
+    fn should_receive_only_one_hit_at_the_same_frame() {
+        let mut app = prepare_app();
+        let player_id = spawn_player(&mut app);
+
+        hit(&mut app, player_id);
+        hit(&mut app, player_id);
+        app.update();
+
+        assert_eq!(health(&app, player_id), 4);
+        assert!(app.world.get::<Invulnerable>(player_id).is_some());
+    }
+
+    #[test]
+    fn should_ignore_damage_while_invulnerable() {
+        let mut app = prepare_app();
+        let player_id = spawn_player(&mut app);
+
+        hit(&mut app, player_id);
+        app.update();
+
+        advance_time(&mut app, Duration::from_millis(500));
+        hit(&mut app, player_id);
+        app.update();
+
+        assert_eq!(health(&app, player_id), 4);
+
+        // Invulnerability lasts 1 second in the test config
+        advance_time(&mut app, Duration::from_millis(600));
+        app.update();
+
+        assert!(app.world.get::<Invulnerable>(player_id).is_none());
+
+        hit(&mut app, player_id);
+        app.update();
+
+        assert_eq!(health(&app, player_id), 3);
+    }
+
+    #[test]
+    fn should_blink_while_invulnerable() {
+        let mut app = prepare_app();
+        let player_id = spawn_player(&mut app);
+
+        hit(&mut app, player_id);
+        app.update();
+
+        let mut alphas = Vec::new();
+
+        for _ in 0..12 {
+            advance_time(&mut app, Duration::from_millis(50));
+            app.update();
+
+            let sprite = app
+                .world
+                .get::<TextureAtlasSprite>(player_id)
+                .expect("Player must have a sprite");
+
+            alphas.push(sprite.color.a());
+        }
+
+        assert!(alphas.iter().any(|alpha| *alpha < 1.0));
+        assert!(alphas.contains(&1.0));
+
+        // Make the player vulnerable again
+        advance_time(&mut app, Duration::from_secs(1));
+        app.update();
+
+        let sprite = app
+            .world
+            .get::<TextureAtlasSprite>(player_id)
+            .expect("Player must have a sprite");
+
+        assert_eq!(sprite.color.a(), 1.0);
+    }
+
+    #[test]
+    fn should_pass_through_enemies_while_invulnerable() {
+        let mut app = prepare_app();
+        let player_id = spawn_player(&mut app);
+
+        hit(&mut app, player_id);
+        app.update();
+
+        let collision_groups = app
+            .world
+            .get::<CollisionGroups>(player_id)
+            .expect("Invulnerable player must have collision groups");
+
+        assert_eq!(collision_groups.filters & ENEMY_COLLISION_GROUP, 0);
+
+        advance_time(&mut app, Duration::from_secs(2));
+        app.update();
+
+        let collision_groups = app
+            .world
+            .get::<CollisionGroups>(player_id)
+            .expect("Player must have collision groups");
+
+        assert_ne!(collision_groups.filters & ENEMY_COLLISION_GROUP, 0);
     }
 }
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
+use std::time::Duration;
 
 use bevy::prelude::*;
 use bevy_inspector_egui::Inspectable;
+use serde::{Deserialize, Serialize};
 
 /// Medium animation duration should
 ///  be from `0.08` to `0.12` seconds
@@ -24,13 +26,6 @@ pub struct FastAnimation {
     pub timer: Timer,
 }
 
-/// X-treme fast animation duration should
-///  be from `0.03` to `0.04`
-#[derive(Component)]
-pub struct XFastAnimation {
-    pub timer: Timer,
-}
-
 #[derive(Component, Default, Inspectable)]
 /// Describes that entity on move or not
 pub struct OnMove(pub bool);
@@ -60,7 +55,7 @@ pub struct Climber {
     pub climbing: bool,
 }
 
-#[derive(Component, Clone, Debug, Inspectable)]
+#[derive(Component, Clone, Debug, Inspectable, Deserialize, Serialize)]
 pub struct Health {
     /// Describes current health
     pub current: i32,
@@ -69,10 +64,72 @@ pub struct Health {
     pub max: i32,
 }
 
-/// If `true` than the entity in attack state
-/// Otherwise - no
+/// Describes the attack swing of the entity.
+/// The swing is synced with the attack sprite sheet:
+///  the hitbox hurts only on active frames which are
+///  between the startup and the recovery frames
+#[derive(Debug, Default, Component, Inspectable)]
+pub struct Attacks {
+    /// Frames of the attack sprite sheet of this entity
+    pub frames: SwingFrames,
+
+    /// Time since the swing has started.
+    /// `None` when the entity doesn't attack
+    pub swing: Option<Duration>,
+
+    /// Time until the entity can attack again
+    pub cooldown: Duration,
+
+    /// Entities which have been hit by the current swing
+    #[inspectable(ignore)]
+    pub hit_entities: HashSet<Entity>,
+}
+
+/// Splits frames of the attack sprite sheet into parts of the swing.
+/// Described next to the attack sheet in `textures.ron`
+#[derive(Debug, Clone, Copy, Default, Deserialize, Inspectable, Eq, PartialEq)]
+pub struct SwingFrames {
+    /// Frames when the attacker swings but doesn't hurt yet
+    pub startup: usize,
+
+    /// Frames when the hitbox hurts
+    pub active: usize,
+
+    /// Frames when the attacker returns to the normal pose
+    pub recovery: usize,
+}
+
+impl SwingFrames {
+    pub fn total(&self) -> usize {
+        self.startup + self.active + self.recovery
+    }
+}
+
+/// How many health points the target loses when the entity hits it
 #[derive(Debug, Component, Inspectable)]
-pub struct Attacks(pub bool);
+pub struct Damage(pub i32);
+
+/// Impulse which pushes the target on the right side of the entity.
+/// It's mirrored for targets on the left side
+#[derive(Debug, Component, Inspectable)]
+pub struct Knockback(pub Vec2);
+
+impl Knockback {
+    /// Returns the impulse for the target depending on which side it is
+    pub fn towards(&self, source: Vec3, target: Vec3) -> Vec2 {
+        if source.x < target.x {
+            self.0
+        } else {
+            Vec2::new(-self.0.x, self.0.y)
+        }
+    }
+}
+
+/// The entity doesn't receive any damage until the timer is finished
+#[derive(Component, Debug)]
+pub struct Invulnerable {
+    pub timer: Timer,
+}
 
 /// Describes that this entity might have receive `Attacks`
 #[derive(Debug, Component, Inspectable)]
@@ -80,3 +137,11 @@ pub struct Attackable;
 
 #[derive(Component, Inspectable, Debug)]
 pub struct Speed(pub f32);
+
+/// Describes that the player may interact with this entity
+///  (open a chest, etc.) when the player stays nearby
+#[derive(Component, Default, Debug, Inspectable)]
+pub struct Interactable {
+    /// Describes that the player is inside the entity sensor
+    pub player_nearby: bool,
+}
@@ -6,8 +6,11 @@ use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
 use bevy_inspector_egui::{Inspectable, InspectorPlugin, RegisterInspectable};
 use iyes_loopless::state::CurrentState;
 
-use crate::common::{Attacks, Climbable, Health, MovementDirection, OnMove, Speed};
-use crate::enemy::{Enemy, EnemyType, Patrol};
+use crate::common::{
+    Attacks, Climbable, Damage, Health, Interactable, Knockback, MovementDirection, OnMove, Speed,
+};
+use crate::enemy::{DropTable, Enemy, EnemyAnimationState, EnemyType, Patrol};
+use crate::items::{DroppedItem, ItemType};
 use crate::player::{JumpState, Player, PlayerAnimationState, SideDetector};
 use crate::tutorial::{Tutorial, TutorialPassed, TutorialType};
 
@@ -50,11 +53,18 @@ impl Plugin for DebugPlugin {
                 .register_inspectable::<MovementDirection>()
                 .register_inspectable::<OnMove>()
                 .register_inspectable::<Attacks>()
+                .register_inspectable::<Damage>()
+                .register_inspectable::<Knockback>()
                 .register_inspectable::<SideDetector>()
                 .register_inspectable::<EnemyType>()
                 .register_inspectable::<Patrol>()
+                .register_inspectable::<DropTable>()
+                .register_inspectable::<EnemyAnimationState>()
                 .register_inspectable::<Health>()
                 .register_inspectable::<JumpState>()
+                .register_inspectable::<Interactable>()
+                .register_inspectable::<ItemType>()
+                .register_inspectable::<DroppedItem>()
                 // .register_inspectable::<PlayerAnimationState>()
                 .add_stage_after(CoreStage::Update, DEBUG, SystemStage::single_threaded())
                 .add_system_to_stage(DEBUG, debug_collisions)
@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    common::Interactable,
+    interaction::InteractEvent,
+    items::{Inventory, ItemType},
+    level_transition::LevelTransitionEvent,
+    player::Player,
+    ApplicationState,
+};
+
+pub struct DoorPlugin;
+
+/// Describes the door on the other side
+#[derive(Debug, Clone)]
+pub struct DoorDestination {
+    pub level_iid: String,
+    pub entity_iid: String,
+}
+
+/// Describes a door which moves the player into another level.
+/// Doors without destination block the way until the player opens them
+#[derive(Component, Default, Debug)]
+pub struct Door {
+    /// Locked doors can't be opened by the player.
+    /// Taken from LDtk `locked` field
+    pub locked: bool,
+
+    /// The item which the player needs to unlock the door.
+    /// Taken from LDtk `lockedWith` field of the top-down map
+    pub locked_with: Option<ItemType>,
+
+    /// Where the player appears after passing the door.
+    /// Taken from LDtk `destination` field
+    pub destination: Option<DoorDestination>,
+
+    /// Describes that the door doesn't block the way anymore
+    pub opened: bool,
+}
+
+impl From<EntityInstance> for Door {
+    fn from(entity_instance: EntityInstance) -> Self {
+        let mut door = Self::default();
+
+        for field_instance in entity_instance.field_instances.iter() {
+            match (field_instance.identifier.as_str(), &field_instance.value) {
+                ("locked", FieldValue::Bool(locked)) => door.locked = *locked,
+                ("lockedWith", FieldValue::Enum(Some(item))) => {
+                    door.locked_with = Some(ItemType::from_ldtk(item));
+                }
+                ("destination", FieldValue::EntityRef(Some(reference))) => {
+                    door.destination = Some(DoorDestination {
+                        level_iid: reference.level_iid.clone(),
+                        entity_iid: reference.entity_iid.clone(),
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        door
+    }
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct DoorBundle {
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+
+    #[from_entity_instance]
+    pub door: Door,
+}
+
+/// Solid part of the door which doesn't allow to pass through it
+#[derive(Component)]
+struct DoorBlocker;
+
+/// Extra space around the blocking door in which the player may open it
+const DOOR_INTERACTION_PADDING: f32 = 4.0;
+
+impl Plugin for DoorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Game)
+                .with_system(spawn_door)
+                .with_system(enter_door)
+                .into(),
+        )
+        // Use the same name as it's covered in "LdtkMap"
+        .register_ldtk_entity::<DoorBundle>("Door");
+    }
+}
+
+fn spawn_door(
+    mut commands: Commands,
+    door_query: Query<(Entity, &Transform, &EntityInstance, &Door), Added<Door>>,
+) {
+    for (door_entity, transform, entity_instance, door) in door_query.iter() {
+        let width = entity_instance.width as f32;
+        let height = entity_instance.height as f32;
+
+        // Locked doors are darker than the other ones
+        let color = if door.locked || door.locked_with.is_some() {
+            Color::rgb_u8(92, 84, 61)
+        } else {
+            Color::rgb_u8(183, 168, 122)
+        };
+
+        let padding = if door.destination.is_none() {
+            DOOR_INTERACTION_PADDING
+        } else {
+            0.0
+        };
+
+        commands
+            .entity(door_entity)
+            .insert(Sensor)
+            .insert(Collider::cuboid(
+                width / 2.0 + padding,
+                height / 2.0 + padding,
+            ))
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(Interactable::default())
+            .insert_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::new(width, height)),
+                    ..Default::default()
+                },
+                transform: *transform,
+                ..Default::default()
+            });
+
+        if door.destination.is_none() {
+            commands.entity(door_entity).with_children(|parent| {
+                parent
+                    .spawn()
+                    .insert(RigidBody::Fixed)
+                    .insert(Collider::cuboid(width / 2.0, height / 2.0))
+                    .insert_bundle(TransformBundle::default())
+                    .insert(DoorBlocker);
+            });
+        }
+    }
+}
+
+/// Unlocks the door with the item from the player inventory.
+/// Moves the player into the destination level or opens
+///  the door when the player interacts with it
+fn enter_door(
+    mut commands: Commands,
+    mut interact_event: EventReader<InteractEvent>,
+    mut door_query: Query<(Entity, &mut Door, &mut Sprite)>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    mut level_transition_event: EventWriter<LevelTransitionEvent>,
+) {
+    for event in interact_event.iter() {
+        if let Ok((door_entity, mut door, mut sprite)) = door_query.get_mut(event.0) {
+            if door.locked || door.opened {
+                continue;
+            }
+
+            if let Some(item) = door.locked_with {
+                let mut inventory = match player_query.get_single_mut() {
+                    Ok(inventory) => inventory,
+                    Err(_) => continue,
+                };
+
+                // The item (e.g. key) stays inside the door
+                if !inventory.remove(item) {
+                    continue;
+                }
+
+                door.locked_with = None;
+            }
+
+            if let Some(destination) = &door.destination {
+                level_transition_event.send(LevelTransitionEvent {
+                    level_iid: destination.level_iid.clone(),
+                    entity_iid: destination.entity_iid.clone(),
+                });
+            } else {
+                door.opened = true;
+
+                // Make the door a bit transparent to show that it's opened
+                sprite.color.set_a(0.3);
+
+                commands.entity(door_entity).despawn_descendants();
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    actions::{key_name, Action},
+    common::Interactable,
+    interaction::InteractEvent,
+    level_transition::LevelTransitionEvent,
+    ron_parsers::Settings,
+    ApplicationState,
+};
+
+pub struct ElevatorPlugin;
+
+/// Describes an elevator which links floors of the top-down map
+#[derive(Component, Default, Debug)]
+pub struct ElevatorLink {
+    /// The level of the linked elevator.
+    /// Taken from LDtk `destination` field
+    pub level_iid: Option<String>,
+
+    /// The linked elevator itself
+    pub entity_iid: Option<String>,
+}
+
+impl From<EntityInstance> for ElevatorLink {
+    fn from(entity_instance: EntityInstance) -> Self {
+        let destination = entity_instance
+            .field_instances
+            .iter()
+            .find(|field| field.identifier == "destination");
+
+        match destination.map(|field| &field.value) {
+            Some(FieldValue::EntityRef(Some(reference))) => Self {
+                level_iid: Some(reference.level_iid.clone()),
+                entity_iid: Some(reference.entity_iid.clone()),
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Text above the elevator which is shown when the player stands on it
+#[derive(Component)]
+struct ElevatorPrompt;
+
+/// Vertical gap between the elevator and its prompt
+const PROMPT_GAP: f32 = 8.0;
+
+#[derive(Bundle, LdtkEntity)]
+struct ElevatorLinkBundle {
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+
+    #[from_entity_instance]
+    pub elevator_link: ElevatorLink,
+}
+
+impl Plugin for ElevatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Game)
+                .with_system(spawn_elevator_link)
+                .with_system(show_elevator_prompt)
+                .with_system(use_elevator)
+                .into(),
+        )
+        // Use the same name as it's covered in "LdtkMap"
+        .register_ldtk_entity::<ElevatorLinkBundle>("ElevatorLink");
+    }
+}
+
+fn spawn_elevator_link(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    elevator_query: Query<(Entity, &Transform, &EntityInstance), Added<ElevatorLink>>,
+) {
+    for (elevator_entity, transform, entity_instance) in elevator_query.iter() {
+        let width = entity_instance.width as f32;
+        let height = entity_instance.height as f32;
+
+        commands
+            .entity(elevator_entity)
+            .insert(Sensor)
+            .insert(Collider::cuboid(width / 2.0, height / 2.0))
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(Interactable::default())
+            .insert_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb_u8(169, 70, 176),
+                    custom_size: Some(Vec2::new(width, height)),
+                    ..Default::default()
+                },
+                transform: *transform,
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(Text2dBundle {
+                        text: Text::from_section(
+                            key_name(settings.controls.key(Action::Interact)),
+                            TextStyle {
+                                font: asset_server.load("fonts/NicoPaint-Monospaced.ttf"),
+                                font_size: 10.0,
+                                color: Color::WHITE,
+                            },
+                        )
+                        .with_alignment(TextAlignment::CENTER),
+                        transform: Transform::from_xyz(0.0, height / 2.0 + PROMPT_GAP, 1.0),
+                        visibility: Visibility { is_visible: false },
+                        ..Default::default()
+                    })
+                    .insert(ElevatorPrompt);
+            });
+    }
+}
+
+/// Shows the interaction prompt only when the player stands on the elevator.
+/// The key might be rebound in settings meanwhile
+fn show_elevator_prompt(
+    settings: Res<Settings>,
+    elevator_query: Query<(&Interactable, &Children), (With<ElevatorLink>, Changed<Interactable>)>,
+    mut prompt_query: Query<(&mut Visibility, &mut Text), With<ElevatorPrompt>>,
+) {
+    for (interactable, children) in elevator_query.iter() {
+        for child in children.iter() {
+            if let Ok((mut visibility, mut text)) = prompt_query.get_mut(*child) {
+                visibility.is_visible = interactable.player_nearby;
+                text.sections[0].value = key_name(settings.controls.key(Action::Interact));
+            }
+        }
+    }
+}
+
+/// Moves the player to the linked elevator on another floor
+fn use_elevator(
+    mut interact_event: EventReader<InteractEvent>,
+    elevator_query: Query<&ElevatorLink>,
+    mut level_transition_event: EventWriter<LevelTransitionEvent>,
+) {
+    for event in interact_event.iter() {
+        if let Ok(elevator_link) = elevator_query.get(event.0) {
+            if let (Some(level_iid), Some(entity_iid)) =
+                (&elevator_link.level_iid, &elevator_link.entity_iid)
+            {
+                level_transition_event.send(LevelTransitionEvent {
+                    level_iid: level_iid.clone(),
+                    entity_iid: entity_iid.clone(),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,383 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::Inspectable;
+use iyes_loopless::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    common::{MediumAnimation, OnMove},
+    ron_parsers::GameTextures,
+    ApplicationState, DamageEvent,
+};
+
+use super::{Enemy, EnemyBehaviour, EnemyState, EnemyType};
+
+pub struct EnemyAnimationPlugin;
+
+/// How long the sprite of the hurt enemy is tinted (in seconds)
+const HURT_FLASH_DURATION: f32 = 0.2;
+
+/// Tint of the sprite during the hurt flash
+const HURT_FLASH_COLOR: Color = Color::rgb(1.0, 0.3, 0.3);
+
+/// Tint of the dead enemy. It fades out while the death animation is played
+const DEATH_COLOR: Color = Color::rgb(0.4, 0.4, 0.4);
+
+/// Describes animation state of the enemy.
+/// Every state has its own sprite sheet in `resources/textures.ron`
+#[derive(Component, Debug, Clone, Copy, Eq, PartialEq, Hash, Inspectable, Deserialize)]
+pub enum EnemyAnimationState {
+    /// Enemy does nothing
+    Idle,
+
+    /// Enemy walks or flies
+    Walk,
+
+    /// Enemy has taken damage but didn't die
+    Hurt,
+
+    /// Enemy winds up the hit
+    Attack,
+
+    /// Enemy died. It's removed when the animation is over
+    Death,
+}
+
+impl Default for EnemyAnimationState {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
+
+/// Tints the sprite of the hurt enemy for a while
+#[derive(Component)]
+struct HurtFlash {
+    timer: Timer,
+}
+
+impl Plugin for EnemyAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            enemy_hurt
+                .run_in_state(ApplicationState::Game)
+                .label("enemy_hurt"),
+        )
+        .add_system(
+            enemy_animation_processor
+                .run_in_state(ApplicationState::Game)
+                .label("enemy_animation_processor")
+                .after("enemy_hurt"),
+        )
+        .add_system(
+            enemy_animation_textures_processor
+                .run_in_state(ApplicationState::Game)
+                .label("enemy_animation_textures_processor")
+                .after("enemy_animation_processor"),
+        )
+        .add_system(
+            enemy_animation
+                .run_in_state(ApplicationState::Game)
+                .after("enemy_animation_textures_processor"),
+        )
+        .add_system(hurt_flash.run_in_state(ApplicationState::Game));
+    }
+}
+
+/// Starts the hurt animation and the flash on every hit
+fn enemy_hurt(
+    mut commands: Commands,
+    mut damage_event: EventReader<DamageEvent>,
+    mut enemy_query: Query<(&mut EnemyAnimationState, &mut TextureAtlasSprite), With<Enemy>>,
+) {
+    for damage in damage_event.iter() {
+        if let Ok((mut state, mut sprite)) = enemy_query.get_mut(damage.target) {
+            if *state == EnemyAnimationState::Death {
+                continue;
+            }
+
+            // The next hit starts the hurt animation from the beginning
+            *state = EnemyAnimationState::Hurt;
+            sprite.color = HURT_FLASH_COLOR;
+
+            commands.entity(damage.target).insert(HurtFlash {
+                timer: Timer::from_seconds(HURT_FLASH_DURATION, false),
+            });
+        }
+    }
+}
+
+/// Selects the animation by what the enemy is doing right now
+fn enemy_animation_processor(
+    mut enemy_query: Query<
+        (&mut EnemyAnimationState, &OnMove, Option<&EnemyBehaviour>),
+        With<Enemy>,
+    >,
+) {
+    for (mut state, on_move, behaviour) in enemy_query.iter_mut() {
+        // Hurt and death animations are played until the end
+        if *state == EnemyAnimationState::Hurt || *state == EnemyAnimationState::Death {
+            continue;
+        }
+
+        let next_state =
+            if behaviour.map_or(false, |behaviour| behaviour.state == EnemyState::Attack) {
+                EnemyAnimationState::Attack
+            } else if on_move.0 {
+                EnemyAnimationState::Walk
+            } else {
+                EnemyAnimationState::Idle
+            };
+
+        if *state != next_state {
+            *state = next_state;
+        }
+    }
+}
+
+/// Changes the sprite sheet when the animation is changed
+fn enemy_animation_textures_processor(
+    materials: Res<GameTextures>,
+    mut enemy_query: Query<
+        (
+            &EnemyType,
+            &EnemyAnimationState,
+            &mut Handle<TextureAtlas>,
+            &mut TextureAtlasSprite,
+            &mut MediumAnimation,
+        ),
+        Changed<EnemyAnimationState>,
+    >,
+) {
+    for (enemy_type, state, mut texture_atlas, mut sprite, mut animation) in enemy_query.iter_mut()
+    {
+        *texture_atlas = materials
+            .enemies
+            .get(enemy_type)
+            .get(*state)
+            .texture
+            .clone();
+        sprite.index = 0;
+        animation.timer.reset();
+    }
+}
+
+/// Loops idle, walk and attack animations.
+/// The hurt animation returns the enemy to other animations
+///  and the death animation fades out and removes the enemy
+fn enemy_animation(
+    mut commands: Commands,
+    time: Res<Time>,
+    materials: Res<GameTextures>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &EnemyType,
+            &mut EnemyAnimationState,
+            &mut TextureAtlasSprite,
+            &mut MediumAnimation,
+        ),
+        With<Enemy>,
+    >,
+) {
+    for (entity, enemy_type, mut state, mut sprite, mut animation) in enemy_query.iter_mut() {
+        animation.timer.tick(time.delta());
+
+        let frames = materials.enemies.get(enemy_type).get(*state).items;
+
+        if *state == EnemyAnimationState::Death {
+            // The current frame is over when the timer has just finished
+            let frame_progress = if animation.timer.just_finished() {
+                1.0
+            } else {
+                animation.timer.percent()
+            };
+            let progress = (sprite.index as f32 + frame_progress) / frames as f32;
+            let mut color = DEATH_COLOR;
+
+            color.set_a(1.0 - progress.min(1.0));
+            sprite.color = color;
+        }
+
+        if !animation.timer.just_finished() {
+            continue;
+        }
+
+        if sprite.index + 1 < frames {
+            sprite.index += 1;
+
+            continue;
+        }
+
+        match *state {
+            EnemyAnimationState::Hurt => {
+                *state = EnemyAnimationState::Idle;
+            }
+            EnemyAnimationState::Death => {
+                commands.entity(entity).despawn_recursive();
+            }
+            _ => {
+                // Loop the animation
+                sprite.index = 0;
+            }
+        }
+    }
+}
+
+fn hurt_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flash_query: Query<(
+        Entity,
+        &EnemyAnimationState,
+        &mut HurtFlash,
+        &mut TextureAtlasSprite,
+    )>,
+) {
+    for (entity, state, mut flash, mut sprite) in flash_query.iter_mut() {
+        flash.timer.tick(time.delta());
+
+        if flash.timer.finished() {
+            // The killing hit flashes too but the dead enemy keeps its tint
+            if *state != EnemyAnimationState::Death {
+                sprite.color = Color::WHITE;
+            }
+
+            commands.entity(entity).remove::<HurtFlash>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod enemy_animations_tests {
+    use std::time::Duration;
+
+    use bevy::ecs::event::Events;
+    use bevy::prelude::*;
+    use iyes_loopless::prelude::*;
+
+    use crate::common::{MediumAnimation, OnMove};
+    use crate::enemy::{Enemy, EnemyType};
+    use crate::tests::sprites_textures::prepare_sprites;
+    use crate::{ApplicationState, DamageEvent};
+
+    use super::{EnemyAnimationPlugin, EnemyAnimationState, DEATH_COLOR, HURT_FLASH_COLOR};
+
+    /// Duration of one frame of enemy animations
+    const FRAME_DURATION: Duration = Duration::from_millis(100);
+
+    /// Prepares the app with the idle enemy. Returns the enemy entity
+    fn prepare_app() -> (App, Entity) {
+        let mut app = App::new();
+
+        let mut time = Time::default();
+        let startup = time.startup();
+
+        // The first update only remembers the instant
+        time.update_with_instant(startup);
+
+        app.insert_resource(prepare_sprites())
+            .insert_resource(time)
+            .add_event::<DamageEvent>()
+            .add_loopless_state(ApplicationState::Game)
+            .add_plugin(EnemyAnimationPlugin);
+
+        let enemy_id = app
+            .world
+            .spawn()
+            .insert(Enemy)
+            .insert(EnemyType(String::from("Durt")))
+            .insert(EnemyAnimationState::Idle)
+            .insert(OnMove(false))
+            .insert(MediumAnimation {
+                timer: Timer::new(FRAME_DURATION, true),
+            })
+            .insert(TextureAtlasSprite::default())
+            .insert(Handle::<TextureAtlas>::default())
+            .id();
+
+        app.update();
+
+        (app, enemy_id)
+    }
+
+    fn advance_frame(app: &mut App) {
+        let mut time = app.world.resource_mut::<Time>();
+        let last_update = time.last_update().unwrap_or_else(|| time.startup());
+
+        time.update_with_instant(last_update + FRAME_DURATION);
+        app.update();
+    }
+
+    fn state(app: &App, enemy_id: Entity) -> EnemyAnimationState {
+        *app.world
+            .get::<EnemyAnimationState>(enemy_id)
+            .expect("Enemy must have an animation state")
+    }
+
+    fn sprite(app: &App, enemy_id: Entity) -> &TextureAtlasSprite {
+        app.world
+            .get::<TextureAtlasSprite>(enemy_id)
+            .expect("Enemy must have a sprite")
+    }
+
+    #[test]
+    fn should_return_to_idle_after_the_hurt_animation() {
+        let (mut app, enemy_id) = prepare_app();
+        let source = app.world.spawn().id();
+
+        app.world
+            .resource_mut::<Events<DamageEvent>>()
+            .send(DamageEvent {
+                source,
+                target: enemy_id,
+                amount: 1,
+                knockback: Vec2::ZERO,
+            });
+        app.update();
+
+        assert_eq!(state(&app, enemy_id), EnemyAnimationState::Hurt);
+        assert_eq!(sprite(&app, enemy_id).color, HURT_FLASH_COLOR);
+
+        // Enemy sheets have 4 frames in the test textures
+        for _ in 0..3 {
+            advance_frame(&mut app);
+
+            assert_eq!(state(&app, enemy_id), EnemyAnimationState::Hurt);
+        }
+
+        advance_frame(&mut app);
+
+        assert_eq!(state(&app, enemy_id), EnemyAnimationState::Idle);
+        assert_eq!(sprite(&app, enemy_id).color, Color::WHITE);
+    }
+
+    #[test]
+    fn should_fade_out_and_despawn_after_the_death_animation() {
+        let (mut app, enemy_id) = prepare_app();
+
+        *app.world
+            .get_mut::<EnemyAnimationState>(enemy_id)
+            .expect("Enemy must have an animation state") = EnemyAnimationState::Death;
+        app.update();
+
+        let mut alphas = Vec::new();
+
+        for _ in 0..3 {
+            advance_frame(&mut app);
+
+            let color = sprite(&app, enemy_id).color;
+
+            assert_eq!(state(&app, enemy_id), EnemyAnimationState::Death);
+            assert_eq!(color.r(), DEATH_COLOR.r());
+
+            alphas.push(color.a());
+        }
+
+        // The corpse becomes more transparent with every frame
+        assert!(alphas.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(alphas[0] < 1.0);
+
+        advance_frame(&mut app);
+
+        assert!(app.world.get_entity(enemy_id).is_none());
+    }
+}
@@ -0,0 +1,884 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    common::{Damage, Health, Knockback, MovementDirection, OnMove, Speed},
+    fixed_timestep::{FixedTimestepAppExt, FIXED_TIMESTEP, FIXED_UPDATE, GAMEPLAY_STAGE},
+    player::Player,
+    projectile::{Projectile, SpawnProjectileEvent},
+    ron_parsers::{BehaviourConfig, ProjectileConfig},
+    ApplicationState, DamageEvent, GameMode,
+};
+
+use super::{Enemy, Patrol};
+
+pub struct EnemyBehaviourPlugin;
+
+/// Distance from which the enemy has reached the player
+const ARRIVAL_DISTANCE: f32 = 4.0;
+
+/// Describes what the enemy is doing right now
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EnemyState {
+    /// Walks between patrol points
+    Patrol,
+
+    /// Runs to the player or to the place where the player has been seen
+    Chase,
+
+    /// Stands still and winds up the hit
+    Attack,
+
+    /// Runs away from the player
+    Flee,
+
+    /// Goes back to the patrol after the player has been lost
+    Return,
+}
+
+/// State machine of the enemy.
+/// Every enemy type has its own config
+#[derive(Component, Debug)]
+pub struct EnemyBehaviour {
+    pub config: BehaviourConfig,
+    pub state: EnemyState,
+
+    /// The player who is seen by the enemy right now
+    pub target: Option<Entity>,
+
+    /// Position where the player has been seen last time
+    pub last_seen: Option<Vec2>,
+
+    /// How long the enemy doesn't see the player
+    pub unseen: Duration,
+
+    /// How long the enemy winds up the current attack
+    pub wind_up: Duration,
+
+    /// Time until the next attack is allowed
+    pub cooldown: Duration,
+}
+
+/// The enemy shoots projectiles at the player
+///  instead of hitting in melee
+#[derive(Component, Debug)]
+pub struct RangedAttack {
+    pub projectile: ProjectileConfig,
+}
+
+impl EnemyBehaviour {
+    pub fn new(config: BehaviourConfig) -> Self {
+        Self {
+            config,
+            state: EnemyState::Patrol,
+            target: None,
+            last_seen: None,
+            unseen: Duration::ZERO,
+            wind_up: Duration::ZERO,
+            cooldown: Duration::ZERO,
+        }
+    }
+
+    fn set_state(&mut self, state: EnemyState) {
+        if self.state != state {
+            self.state = state;
+            self.wind_up = Duration::ZERO;
+        }
+    }
+}
+
+impl Plugin for EnemyBehaviourPlugin {
+    fn build(&self, app: &mut App) {
+        // Enemies move on the fixed timestep as the player does
+        app.add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            enemy_perception
+                .run_in_state(ApplicationState::Game)
+                .label("enemy_perception"),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            update_enemy_state
+                .run_in_state(ApplicationState::Game)
+                .label("enemy_state")
+                .after("enemy_perception"),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            enemy_movement
+                .run_in_state(ApplicationState::Game)
+                .after("enemy_state"),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            enemy_attack
+                .run_in_state(ApplicationState::Game)
+                .after("enemy_state"),
+        );
+    }
+}
+
+/// The enemy sees the player when the player is close enough
+///  and nothing blocks the line of sight
+fn enemy_perception(
+    rapier_context: Res<RapierContext>,
+    player_query: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut enemy_query: Query<(Entity, &GlobalTransform, &mut EnemyBehaviour), With<Enemy>>,
+) {
+    for (enemy_entity, enemy_transform, mut behaviour) in enemy_query.iter_mut() {
+        let enemy_position = enemy_transform.translation().truncate();
+        let sight_range = behaviour.config.sight_range;
+
+        let seen_player = player_query
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation().truncate()))
+            .find(|(player_entity, player_position)| {
+                let offset = *player_position - enemy_position;
+                let distance = offset.length();
+
+                if distance > sight_range {
+                    return false;
+                }
+
+                if distance == 0.0 {
+                    return true;
+                }
+
+                // Walls and other enemies block the view
+                let hit = rapier_context.cast_ray(
+                    enemy_position,
+                    offset / distance,
+                    distance,
+                    true,
+                    QueryFilter::new()
+                        .exclude_sensors()
+                        .exclude_collider(enemy_entity),
+                );
+
+                matches!(hit, Some((entity, _)) if entity == *player_entity)
+            });
+
+        if let Some((player_entity, player_position)) = seen_player {
+            behaviour.target = Some(player_entity);
+            behaviour.last_seen = Some(player_position);
+            behaviour.unseen = Duration::ZERO;
+        } else {
+            behaviour.target = None;
+            behaviour.unseen += FIXED_TIMESTEP;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_enemy_state(
+    game_mode: Res<GameMode>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    mut enemy_query: Query<
+        (
+            &Transform,
+            &GlobalTransform,
+            &Health,
+            &Patrol,
+            &mut EnemyBehaviour,
+        ),
+        With<Enemy>,
+    >,
+) {
+    for (transform, global_transform, health, patrol, mut behaviour) in enemy_query.iter_mut() {
+        behaviour.cooldown = behaviour.cooldown.saturating_sub(FIXED_TIMESTEP);
+
+        // Distances to patrol points are measured as the enemy moves
+        let moves_freely = moves_freely(*game_mode, patrol);
+
+        // Patrol points are placed inside the level
+        let local_position = transform.translation.truncate();
+        let position = global_transform.translation().truncate();
+        let player_position = behaviour
+            .target
+            .and_then(|player| player_query.get(player).ok())
+            .map(|player_transform| player_transform.translation().truncate());
+
+        let config = &behaviour.config;
+        let weak = config.flee_health > 0 && health.current <= config.flee_health;
+        let forgotten = behaviour.unseen.as_secs_f32() >= config.forget_time;
+        // The walking enemy under the player on a ledge keeps chasing
+        //  instead of swinging at the air
+        let can_attack = player_position.map_or(false, |player_position| {
+            in_reach(position, player_position, config.attack_range) && behaviour.cooldown.is_zero()
+        });
+        let on_patrol = patrol.point().map_or(true, |point| {
+            travel_distance(moves_freely, local_position, point) <= patrol.arrival_radius
+        });
+
+        let next_state = match (behaviour.state, player_position) {
+            // The weak enemy runs away while it remembers the player
+            (_, Some(_)) if weak => EnemyState::Flee,
+            (EnemyState::Flee, None) if !forgotten => EnemyState::Flee,
+
+            // The started attack is finished even if the player has stepped away
+            (EnemyState::Attack, _) => EnemyState::Attack,
+            (_, Some(_)) if can_attack => EnemyState::Attack,
+            (_, Some(_)) => EnemyState::Chase,
+            (EnemyState::Chase, None) if !forgotten => EnemyState::Chase,
+
+            (EnemyState::Patrol, None) => EnemyState::Patrol,
+            (_, None) if on_patrol => EnemyState::Patrol,
+            (_, None) => EnemyState::Return,
+        };
+
+        behaviour.set_state(next_state);
+    }
+}
+
+/// Hits the player when the wind-up is over.
+/// The player might step away or jump over the enemy meanwhile.
+/// Ranged enemies shoot at the player instead
+#[allow(clippy::type_complexity)]
+fn enemy_attack(
+    player_query: Query<&GlobalTransform, With<Player>>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Damage,
+            &Knockback,
+            &mut EnemyBehaviour,
+            Option<&RangedAttack>,
+        ),
+        With<Enemy>,
+    >,
+    mut damage_event: EventWriter<DamageEvent>,
+    mut spawn_projectile_event: EventWriter<SpawnProjectileEvent>,
+) {
+    for (enemy_entity, enemy_transform, damage, knockback, mut behaviour, ranged_attack) in
+        enemy_query.iter_mut()
+    {
+        if behaviour.state != EnemyState::Attack {
+            continue;
+        }
+
+        behaviour.wind_up += FIXED_TIMESTEP;
+
+        if behaviour.wind_up.as_secs_f32() < behaviour.config.wind_up {
+            continue;
+        }
+
+        let player = behaviour
+            .target
+            .and_then(|player| player_query.get(player).ok().map(|t| (player, t)));
+
+        if let Some((player_entity, player_transform)) = player {
+            let offset =
+                (player_transform.translation() - enemy_transform.translation()).truncate();
+
+            if let Some(ranged_attack) = ranged_attack {
+                let projectile = &ranged_attack.projectile;
+
+                // The projectile flies to the place where the player is
+                //  so the player is able to dodge it
+                spawn_projectile_event.send(SpawnProjectileEvent {
+                    projectile: Projectile {
+                        shooter: enemy_entity,
+                        damage: damage.0,
+                        knockback: knockback.0,
+                        lifetime: Duration::from_secs_f32(projectile.lifetime),
+                    },
+                    translation: enemy_transform.translation(),
+                    velocity: offset.normalize_or_zero() * projectile.speed,
+                    size: projectile.size,
+                });
+            } else if in_reach(
+                enemy_transform.translation().truncate(),
+                player_transform.translation().truncate(),
+                behaviour.config.attack_range,
+            ) {
+                damage_event.send(DamageEvent {
+                    source: enemy_entity,
+                    target: player_entity,
+                    amount: damage.0,
+                    knockback: knockback.towards(
+                        enemy_transform.translation(),
+                        player_transform.translation(),
+                    ),
+                });
+            }
+        }
+
+        behaviour.cooldown = Duration::from_secs_f32(behaviour.config.attack_cooldown);
+        behaviour.set_state(EnemyState::Chase);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn enemy_movement(
+    game_mode: Res<GameMode>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    mut enemy_query: Query<
+        (
+            &Transform,
+            &GlobalTransform,
+            &Speed,
+            &EnemyBehaviour,
+            &mut OnMove,
+            &mut Velocity,
+            &mut Patrol,
+            &mut MovementDirection,
+            &mut TextureAtlasSprite,
+            Option<&RangedAttack>,
+        ),
+        With<Enemy>,
+    >,
+) {
+    for (
+        transform,
+        global_transform,
+        speed,
+        behaviour,
+        mut on_move,
+        mut velocity,
+        mut patrol,
+        mut direction,
+        mut sprite,
+        ranged_attack,
+    ) in enemy_query.iter_mut()
+    {
+        // Patrol points are placed inside the level
+        //  while the player is seen in the world
+        let local_position = transform.translation.truncate();
+        let position = global_transform.translation().truncate();
+        let player_position = behaviour
+            .target
+            .and_then(|player| player_query.get(player).ok())
+            .map(|player_transform| player_transform.translation().truncate())
+            .or(behaviour.last_seen);
+
+        let moves_freely = moves_freely(*game_mode, &patrol);
+        let movement = Movement {
+            moves_freely,
+            velocity: velocity.linvel,
+        };
+
+        // Ranged enemies keep the distance to the seen player
+        //  and go to the place where the player has been lost
+        let chase_distance = match (ranged_attack, behaviour.target) {
+            (Some(_), Some(_)) => behaviour.config.attack_range,
+            _ => ARRIVAL_DISTANCE,
+        };
+
+        let new_velocity = match behaviour.state {
+            EnemyState::Patrol => follow_patrol(local_position, speed.0, &movement, &mut patrol),
+            EnemyState::Chase => player_position.map(|player_position| {
+                movement.towards(
+                    position,
+                    player_position,
+                    behaviour.config.chase_speed,
+                    chase_distance,
+                )
+            }),
+            EnemyState::Attack => {
+                // Face the player during the wind-up
+                if let Some(player_position) = player_position {
+                    face(&mut direction, player_position.x - position.x);
+                }
+
+                Some(movement.stop())
+            }
+            EnemyState::Flee => player_position.map(|player_position| {
+                movement.towards(
+                    position,
+                    position * 2.0 - player_position,
+                    behaviour.config.chase_speed,
+                    ARRIVAL_DISTANCE,
+                )
+            }),
+            EnemyState::Return => patrol.point().map(|point| {
+                movement.towards(local_position, point, speed.0, patrol.arrival_radius)
+            }),
+        };
+
+        let new_velocity = match new_velocity {
+            Some(new_velocity) => new_velocity,
+            None => {
+                on_move.0 = false;
+
+                continue;
+            }
+        };
+
+        if behaviour.state != EnemyState::Attack {
+            face(&mut direction, new_velocity.x);
+        }
+
+        sprite.flip_x = *direction == MovementDirection::Left;
+        on_move.0 = new_velocity.x != 0.0 || (moves_freely && new_velocity.y != 0.0);
+        velocity.linvel = new_velocity;
+    }
+}
+
+/// Turns the entity to the side of the horizontal offset
+fn face(direction: &mut MovementDirection, offset_x: f32) {
+    if offset_x > 0.0 {
+        *direction = MovementDirection::Right;
+    } else if offset_x < 0.0 {
+        *direction = MovementDirection::Left;
+    }
+}
+
+/// Flying enemies and all enemies of the top-down map
+///  move in both axes
+fn moves_freely(game_mode: GameMode, patrol: &Patrol) -> bool {
+    game_mode == GameMode::TopDown || patrol.flying
+}
+
+/// Returns the distance which the enemy has to pass to the destination.
+/// Walking enemies in the platformer can't change the height
+///  so only the horizontal distance is counted for them
+fn travel_distance(moves_freely: bool, position: Vec2, destination: Vec2) -> f32 {
+    if moves_freely {
+        position.distance(destination)
+    } else {
+        (destination.x - position.x).abs()
+    }
+}
+
+/// The hit lands only when the target is within the attack range in any direction
+fn in_reach(position: Vec2, target: Vec2, attack_range: f32) -> bool {
+    position.distance(target) <= attack_range
+}
+
+/// Describes how the enemy is able to move
+struct Movement {
+    /// Walking enemies in the platformer move only horizontally
+    ///  and fall by the gravity
+    moves_freely: bool,
+
+    /// Current velocity of the enemy
+    velocity: Vec2,
+}
+
+impl Movement {
+    fn arrived(&self, position: Vec2, destination: Vec2, arrival_radius: f32) -> bool {
+        travel_distance(self.moves_freely, position, destination) <= arrival_radius
+    }
+
+    fn stop(&self) -> Vec2 {
+        if self.moves_freely {
+            Vec2::ZERO
+        } else {
+            Vec2::new(0.0, self.velocity.y)
+        }
+    }
+
+    /// Returns the velocity which moves the enemy to the destination
+    fn towards(&self, position: Vec2, destination: Vec2, speed: f32, arrival_radius: f32) -> Vec2 {
+        if self.arrived(position, destination, arrival_radius) {
+            return self.stop();
+        }
+
+        let offset = destination - position;
+
+        if self.moves_freely {
+            offset.normalize() * speed
+        } else {
+            Vec2::new(offset.x.signum() * speed, self.velocity.y)
+        }
+    }
+}
+
+/// Goes to the current patrol point, waits there
+///  and selects the next point by the patrol mode
+fn follow_patrol(
+    position: Vec2,
+    speed: f32,
+    movement: &Movement,
+    patrol: &mut Patrol,
+) -> Option<Vec2> {
+    // Do nothing if we have no patrol or it's equal to 1
+    if patrol.points.len() <= 1 {
+        return None;
+    }
+
+    if !patrol.wait.is_zero() {
+        patrol.wait = patrol.wait.saturating_sub(FIXED_TIMESTEP);
+
+        return Some(movement.stop());
+    }
+
+    let point = patrol.points[patrol.index];
+
+    if movement.arrived(position, point, patrol.arrival_radius) {
+        let wait = patrol.wait_at(patrol.index);
+
+        // The one-shot patrol stays on the last point forever
+        if patrol.advance() {
+            patrol.wait = wait;
+        }
+
+        return Some(movement.stop());
+    }
+
+    Some(movement.towards(position, point, speed, patrol.arrival_radius))
+}
+
+#[cfg(test)]
+mod enemy_behaviour_tests {
+    use std::time::Duration;
+
+    use bevy::asset::AssetPlugin;
+    use bevy::ecs::event::{Events, ManualEventReader};
+    use bevy::hierarchy::HierarchyPlugin;
+    use bevy::prelude::*;
+    use bevy::transform::TransformPlugin;
+    use bevy_rapier2d::prelude::*;
+    use iyes_loopless::prelude::*;
+
+    use crate::actions::Action;
+    use crate::common::{Damage, Health, Knockback, MovementDirection, OnMove, Speed};
+    use crate::enemy::{Enemy, Patrol, PatrolMode};
+    use crate::fixed_timestep::{FixedTimestepPlugin, ForcedFrameDuration, FIXED_TIMESTEP};
+    use crate::physics::PhysicsPlugin;
+    use crate::player::Player;
+    use crate::projectile::SpawnProjectileEvent;
+    use crate::ron_parsers::BehaviourConfig;
+    use crate::tests::enemies::{prepare_behaviour_config, prepare_patrol};
+    use crate::{ApplicationState, DamageEvent, GameMode};
+
+    use super::{follow_patrol, EnemyBehaviour, EnemyBehaviourPlugin, EnemyState, Movement};
+
+    /// The walking enemy which is falling down
+    const WALKING: Movement = Movement {
+        moves_freely: false,
+        velocity: Vec2::new(0.0, -10.0),
+    };
+
+    /// Prepares the headless app with physics where every update
+    ///  is one step of the game. Returns the enemy and the player entities
+    fn prepare_behaviour_app(config: BehaviourConfig, health: i32) -> (App, Entity, Entity) {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            // Rapier looks for meshes and scenes of async colliders
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<Scene>()
+            .insert_resource(GameMode::Platformer)
+            .insert_resource(Input::<Action>::default())
+            .insert_resource(ForcedFrameDuration(FIXED_TIMESTEP))
+            .add_event::<DamageEvent>()
+            .add_event::<SpawnProjectileEvent>()
+            .add_loopless_state(ApplicationState::Game)
+            .add_plugin(FixedTimestepPlugin)
+            .add_plugin(PhysicsPlugin)
+            .add_plugin(EnemyBehaviourPlugin);
+
+        let enemy_id = app
+            .world
+            .spawn()
+            .insert(Enemy)
+            .insert(Collider::cuboid(5.0, 5.0))
+            .insert(Health {
+                current: health,
+                max: 3,
+            })
+            .insert(Speed(80.0))
+            .insert(OnMove(false))
+            .insert(Velocity::zero())
+            .insert(MovementDirection::Right)
+            .insert(TextureAtlasSprite::default())
+            .insert(Damage(1))
+            .insert(Knockback(Vec2::new(100.0, 30.0)))
+            .insert(Patrol {
+                points: vec![Vec2::new(-50.0, 0.0), Vec2::new(50.0, 0.0)],
+                index: 1,
+                mode: PatrolMode::PingPong,
+                forward: true,
+                waits: Vec::new(),
+                wait: Duration::ZERO,
+                arrival_radius: 4.0,
+                flying: false,
+            })
+            .insert(EnemyBehaviour::new(config))
+            .insert_bundle(TransformBundle::default())
+            .id();
+
+        // The player is far away from the enemy at the start
+        let player_id = app
+            .world
+            .spawn()
+            .insert(Player)
+            .insert(Collider::cuboid(5.0, 5.0))
+            .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                500.0, 0.0, 0.0,
+            )))
+            .id();
+
+        app.update();
+
+        (app, enemy_id, player_id)
+    }
+
+    fn move_player(app: &mut App, player_id: Entity, x: f32, y: f32) {
+        let mut transform = app
+            .world
+            .get_mut::<Transform>(player_id)
+            .expect("Player must have a transform");
+
+        transform.translation = Vec3::new(x, y, 0.0);
+    }
+
+    fn state(app: &App, enemy_id: Entity) -> EnemyState {
+        app.world
+            .get::<EnemyBehaviour>(enemy_id)
+            .expect("Enemy must have a behaviour")
+            .state
+    }
+
+    /// Updates the app until the enemy changes the state.
+    /// Returns the new state and how many steps it took
+    fn update_until_state_changes(app: &mut App, enemy_id: Entity) -> (EnemyState, usize) {
+        let initial_state = state(app, enemy_id);
+
+        for steps in 1..=200 {
+            app.update();
+
+            let current_state = state(app, enemy_id);
+
+            if current_state != initial_state {
+                return (current_state, steps);
+            }
+        }
+
+        panic!("Enemy must leave {:?} state", initial_state);
+    }
+
+    #[test]
+    fn should_walk_to_the_patrol_point() {
+        let mut patrol = prepare_patrol(PatrolMode::PingPong, Vec::new());
+
+        patrol.index = 1;
+
+        // Walking enemies keep the falling speed
+        let velocity = follow_patrol(Vec2::new(0.0, 20.0), 80.0, &WALKING, &mut patrol);
+
+        assert_eq!(velocity, Some(Vec2::new(80.0, -10.0)));
+        assert_eq!(patrol.index, 1);
+
+        let flying = Movement {
+            moves_freely: true,
+            velocity: Vec2::ZERO,
+        };
+        let velocity = follow_patrol(Vec2::new(50.0, 50.0), 80.0, &flying, &mut patrol);
+
+        assert_eq!(velocity, Some(Vec2::new(0.0, -80.0)));
+    }
+
+    #[test]
+    fn should_select_the_next_point_inside_the_arrival_radius() {
+        let mut patrol = prepare_patrol(PatrolMode::Loop, Vec::new());
+
+        patrol.index = 1;
+
+        // Only the horizontal distance matters for walking enemies
+        let velocity = follow_patrol(Vec2::new(53.0, 30.0), 80.0, &WALKING, &mut patrol);
+
+        assert_eq!(velocity, Some(Vec2::new(0.0, -10.0)));
+        assert_eq!(patrol.index, 2);
+
+        // The radius isn't reached yet
+        patrol.index = 1;
+        follow_patrol(Vec2::new(45.0, 0.0), 80.0, &WALKING, &mut patrol);
+
+        assert_eq!(patrol.index, 1);
+    }
+
+    #[test]
+    fn should_wait_on_the_patrol_point() {
+        let mut patrol = prepare_patrol(PatrolMode::Loop, vec![0.0, 0.1]);
+
+        patrol.index = 1;
+
+        // Arrives and starts to wait
+        follow_patrol(Vec2::new(50.0, 0.0), 80.0, &WALKING, &mut patrol);
+
+        assert_eq!(patrol.index, 2);
+        assert_eq!(patrol.wait, Duration::from_secs_f32(0.1));
+
+        let mut waiting_steps = 0;
+
+        while !patrol.wait.is_zero() {
+            let velocity = follow_patrol(Vec2::new(50.0, 0.0), 80.0, &WALKING, &mut patrol);
+
+            assert_eq!(velocity, Some(Vec2::new(0.0, -10.0)));
+
+            waiting_steps += 1;
+        }
+
+        assert_eq!(
+            waiting_steps,
+            (0.1 / FIXED_TIMESTEP.as_secs_f32()).ceil() as usize
+        );
+
+        // Goes to the next point after the wait
+        let velocity = follow_patrol(Vec2::new(50.0, 0.0), 80.0, &WALKING, &mut patrol);
+
+        assert_eq!(velocity, Some(Vec2::new(80.0, -10.0)));
+    }
+
+    #[test]
+    fn should_stay_on_the_last_point_of_the_one_shot_patrol() {
+        let mut patrol = prepare_patrol(PatrolMode::OneShot, vec![0.0, 0.0, 1.0]);
+
+        patrol.index = 2;
+
+        for _ in 0..3 {
+            let velocity = follow_patrol(Vec2::new(100.0, 0.0), 80.0, &WALKING, &mut patrol);
+
+            assert_eq!(velocity, Some(Vec2::new(0.0, -10.0)));
+        }
+
+        // The wait of the last point isn't used because the patrol is over
+        assert_eq!(patrol.index, 2);
+        assert!(patrol.wait.is_zero());
+    }
+
+    #[test]
+    fn should_not_patrol_with_one_point() {
+        let mut patrol = prepare_patrol(PatrolMode::Loop, Vec::new());
+
+        patrol.points.truncate(1);
+
+        assert_eq!(
+            follow_patrol(Vec2::new(50.0, 0.0), 80.0, &WALKING, &mut patrol),
+            None
+        );
+    }
+
+    #[test]
+    fn should_chase_and_attack_the_seen_player() {
+        let (mut app, enemy_id, player_id) = prepare_behaviour_app(prepare_behaviour_config(), 3);
+        let mut damage_reader = ManualEventReader::<DamageEvent>::default();
+
+        assert_eq!(state(&app, enemy_id), EnemyState::Patrol);
+
+        move_player(&mut app, player_id, 60.0, 0.0);
+
+        assert_eq!(
+            update_until_state_changes(&mut app, enemy_id).0,
+            EnemyState::Chase
+        );
+
+        move_player(&mut app, player_id, 10.0, 0.0);
+
+        assert_eq!(
+            update_until_state_changes(&mut app, enemy_id).0,
+            EnemyState::Attack
+        );
+
+        // The enemy hits after the wind-up and chases during the cooldown
+        let (next_state, wind_up_steps) = update_until_state_changes(&mut app, enemy_id);
+        let events = app.world.resource::<Events<DamageEvent>>();
+        let damages: Vec<&DamageEvent> = damage_reader.iter(events).collect();
+
+        // The wind-up starts on the same step as the attack
+        let wind_up = FIXED_TIMESTEP * (wind_up_steps as u32 + 1);
+
+        assert_eq!(next_state, EnemyState::Chase);
+        assert!(wind_up >= Duration::from_secs_f32(0.5));
+        assert_eq!(damages.len(), 1);
+        assert_eq!(damages[0].source, enemy_id);
+        assert_eq!(damages[0].target, player_id);
+
+        for _ in 0..10 {
+            app.update();
+        }
+
+        assert_eq!(state(&app, enemy_id), EnemyState::Chase);
+    }
+
+    #[test]
+    fn should_attack_the_player_above_from_the_place_where_it_stops() {
+        let (mut app, enemy_id, player_id) = prepare_behaviour_app(prepare_behaviour_config(), 3);
+        let mut damage_reader = ManualEventReader::<DamageEvent>::default();
+
+        // The walking enemy stops under the player
+        //  so it must be able to hit from there
+        move_player(&mut app, player_id, 2.0, 15.0);
+
+        assert_eq!(
+            update_until_state_changes(&mut app, enemy_id).0,
+            EnemyState::Attack
+        );
+
+        update_until_state_changes(&mut app, enemy_id);
+
+        let events = app.world.resource::<Events<DamageEvent>>();
+        let damages: Vec<&DamageEvent> = damage_reader.iter(events).collect();
+
+        assert_eq!(damages.len(), 1);
+        assert_eq!(damages[0].target, player_id);
+    }
+
+    #[test]
+    fn should_not_attack_the_player_out_of_reach_above() {
+        let (mut app, enemy_id, player_id) = prepare_behaviour_app(prepare_behaviour_config(), 3);
+        let mut damage_reader = ManualEventReader::<DamageEvent>::default();
+
+        // The player stands on a ledge higher than the attack range
+        move_player(&mut app, player_id, 2.0, 30.0);
+
+        for _ in 0..100 {
+            app.update();
+        }
+
+        let events = app.world.resource::<Events<DamageEvent>>();
+
+        assert_eq!(state(&app, enemy_id), EnemyState::Chase);
+        assert_eq!(damage_reader.iter(events).count(), 0);
+    }
+
+    #[test]
+    fn should_flee_when_the_health_is_low() {
+        let config = BehaviourConfig {
+            flee_health: 1,
+            ..prepare_behaviour_config()
+        };
+        let (mut app, enemy_id, player_id) = prepare_behaviour_app(config, 1);
+
+        move_player(&mut app, player_id, 10.0, 0.0);
+
+        assert_eq!(
+            update_until_state_changes(&mut app, enemy_id).0,
+            EnemyState::Flee
+        );
+    }
+
+    #[test]
+    fn should_return_to_the_patrol_after_the_player_is_forgotten() {
+        let (mut app, enemy_id, player_id) = prepare_behaviour_app(prepare_behaviour_config(), 3);
+
+        move_player(&mut app, player_id, 60.0, 0.0);
+
+        assert_eq!(
+            update_until_state_changes(&mut app, enemy_id).0,
+            EnemyState::Chase
+        );
+
+        // The player runs out of the sight range
+        move_player(&mut app, player_id, 500.0, 0.0);
+
+        let (next_state, steps) = update_until_state_changes(&mut app, enemy_id);
+
+        // The patrol point is far from the enemy
+        assert_eq!(next_state, EnemyState::Return);
+        assert!(FIXED_TIMESTEP * steps as u32 >= Duration::from_secs_f32(1.0));
+    }
+}
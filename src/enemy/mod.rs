@@ -0,0 +1,635 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_inspector_egui::Inspectable;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+use serde::Deserialize;
+
+mod enemy_animations;
+mod enemy_behaviour;
+
+pub use enemy_animations::EnemyAnimationState;
+pub use enemy_behaviour::{EnemyBehaviour, EnemyState, RangedAttack};
+
+use enemy_animations::EnemyAnimationPlugin;
+use enemy_behaviour::EnemyBehaviourPlugin;
+
+use crate::{
+    combat::ENEMY_COLLISION_GROUP,
+    common::{
+        Attackable, Damage, Health, Knockback, MediumAnimation, MovementDirection, OnMove, Speed,
+    },
+    items::{DropItemEvent, ItemType},
+    physics::RenderInterpolation,
+    ron_parsers::{EnemiesConfig, GameTextures},
+    save::GameProgress,
+    ApplicationState,
+};
+
+pub struct EnemyPlugin;
+
+/// Horizontal gap between items which drop from the enemy
+const DROPPED_ITEMS_GAP: f32 = 14.0;
+
+/// Distance from which the patrol point is reached
+///  if the map doesn't describe it
+const DEFAULT_ARRIVAL_RADIUS: f32 = 4.0;
+
+#[derive(Component, Default)]
+pub struct Enemy;
+
+/// Value of the `enemy_type` field in the LDtk map.
+/// Every value is described by its archetype in `resources/enemies.ron`
+#[derive(Component, Clone, Debug, Eq, PartialEq, Hash, Inspectable, Deserialize)]
+#[serde(transparent)]
+pub struct EnemyType(pub String);
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Game)
+                .with_system(spawn_enemy)
+                .with_system(dead)
+                .into(),
+        )
+        .add_plugin(EnemyBehaviourPlugin)
+        .add_plugin(EnemyAnimationPlugin)
+        // Use the same name as it's covered in "LdtkMap"
+        .register_ldtk_entity::<EnemyBundle>("Mob");
+    }
+}
+
+impl From<EntityInstance> for EnemyType {
+    fn from(entity_instance: EntityInstance) -> Self {
+        if let Some(field_instance) = entity_instance
+            .field_instances
+            .iter()
+            .find(|enemy_type| enemy_type.identifier == *"enemy_type")
+        {
+            return match &field_instance.value {
+                FieldValue::Enum(Some(value)) => Self(value.clone()),
+                _ => {
+                    panic!("Cound't find any covered enum");
+                }
+            };
+        }
+
+        panic!("Coundn't find any available options. Please check Ldtk map 'enemy_type' enum");
+    }
+}
+
+fn ldtk_pixel_coords_to_translation_pivoted(
+    ldtk_coords: IVec2,
+    ldtk_pixel_height: i32,
+    entity_size: IVec2,
+    pivot: Vec2,
+) -> Vec2 {
+    let pivot_point = IVec2::new(ldtk_coords.x, ldtk_pixel_height - ldtk_coords.y).as_vec2();
+    let adjusted_pivot = Vec2::new(0.5 - pivot.x, pivot.y - 0.5);
+    let offset = entity_size.as_vec2() * adjusted_pivot;
+
+    pivot_point + offset
+}
+
+/// Describes what the enemy does after the last patrol point
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Inspectable)]
+pub enum PatrolMode {
+    /// Goes from the last point to the first one
+    Loop,
+
+    /// Goes back through all points in the reverse order
+    PingPong,
+
+    /// Stays on the last point
+    OneShot,
+}
+
+/// Describes patrolling for an enemy
+/// E.g. movement on the map
+#[derive(Debug, Component, Inspectable)]
+pub struct Patrol {
+    /// Points which describes when the monster should stop
+    /// Describes the main points for patroling
+    pub points: Vec<Vec2>,
+
+    /// Describes current index in points.
+    /// The index should be inside `points.len()` because
+    /// `index` is index inside points vector
+    pub index: usize,
+
+    pub mode: PatrolMode,
+
+    /// Describes that the ping-pong patrol goes
+    ///  from the first point to the last one
+    pub forward: bool,
+
+    /// Seconds which the enemy waits on every point.
+    /// Points without the value are passed without waiting
+    pub waits: Vec<f32>,
+
+    /// Time which the enemy still waits on the current point
+    pub wait: Duration,
+
+    /// Distance from which the point is reached
+    pub arrival_radius: f32,
+
+    /// Flying enemies ignore the gravity and follow 2D paths
+    pub flying: bool,
+}
+
+impl Patrol {
+    /// Returns the point where the enemy goes
+    pub fn point(&self) -> Option<Vec2> {
+        self.points
+            .get(self.index)
+            .or_else(|| self.points.first())
+            .copied()
+    }
+
+    /// Returns how long the enemy waits on the point
+    pub fn wait_at(&self, index: usize) -> Duration {
+        let seconds = self.waits.get(index).copied().unwrap_or(0.0);
+
+        Duration::from_secs_f32(seconds.max(0.0))
+    }
+
+    /// Selects the next point by the mode.
+    /// Returns `false` when the one-shot patrol is over
+    pub fn advance(&mut self) -> bool {
+        let last_index = self.points.len() - 1;
+
+        match self.mode {
+            PatrolMode::Loop => {
+                self.index = (self.index + 1) % self.points.len();
+            }
+            PatrolMode::PingPong => {
+                if self.index == last_index {
+                    self.forward = false;
+                } else if self.index == 0 {
+                    self.forward = true;
+                }
+
+                if self.forward {
+                    self.index += 1;
+                } else {
+                    self.index -= 1;
+                }
+            }
+            PatrolMode::OneShot => {
+                if self.index == last_index {
+                    return false;
+                }
+
+                self.index += 1;
+            }
+        }
+
+        true
+    }
+}
+
+impl LdtkEntity for Patrol {
+    fn bundle_entity(
+        entity_instance: &EntityInstance,
+        layer_instance: &LayerInstance,
+        _: Option<&Handle<Image>>,
+        _: Option<&TilesetDefinition>,
+        _: &AssetServer,
+        _: &mut Assets<TextureAtlas>,
+    ) -> Self {
+        let mut points = vec![ldtk_pixel_coords_to_translation_pivoted(
+            entity_instance.px,
+            layer_instance.c_hei * layer_instance.grid_size,
+            IVec2::new(entity_instance.width, entity_instance.height),
+            entity_instance.pivot,
+        )];
+
+        let ldtk_patrol = entity_instance
+            .field_instances
+            .iter()
+            .find(|f| f.identifier == *"patrol")
+            .expect("Should have 'patrol' field");
+
+        if let FieldValue::Points(ldtk_points) = &ldtk_patrol.value {
+            for ldtk_point in ldtk_points.iter().flatten() {
+                let pixel_coords = (ldtk_point.as_vec2() + Vec2::new(0.5, 1.0))
+                    * Vec2::splat(layer_instance.grid_size as f32);
+
+                points.push(ldtk_pixel_coords_to_translation_pivoted(
+                    pixel_coords.as_ivec2(),
+                    layer_instance.c_hei * layer_instance.grid_size,
+                    IVec2::new(entity_instance.width, entity_instance.height),
+                    entity_instance.pivot,
+                ));
+            }
+        }
+
+        let field = |identifier: &str| {
+            entity_instance
+                .field_instances
+                .iter()
+                .find(|field| field.identifier == *identifier)
+                .map(|field| &field.value)
+        };
+
+        let mode = match field("patrol_mode") {
+            Some(FieldValue::Enum(Some(mode)) | FieldValue::String(Some(mode))) => {
+                match mode.as_str() {
+                    "Loop" => PatrolMode::Loop,
+                    "PingPong" => PatrolMode::PingPong,
+                    "OneShot" => PatrolMode::OneShot,
+                    _ => {
+                        panic!("Patrol mode might be only 'Loop', 'PingPong' or 'OneShot'");
+                    }
+                }
+            }
+            _ => PatrolMode::PingPong,
+        };
+
+        // The first value is for the spawn position
+        //  and the rest are for `patrol` points
+        let waits = match field("patrol_waits") {
+            Some(FieldValue::Floats(waits)) => {
+                waits.iter().map(|wait| wait.unwrap_or(0.0)).collect()
+            }
+            Some(FieldValue::Ints(waits)) => {
+                waits.iter().map(|wait| wait.unwrap_or(0) as f32).collect()
+            }
+            _ => Vec::new(),
+        };
+
+        let arrival_radius = field("arrival_radius")
+            .and_then(ldtk_number)
+            .unwrap_or(DEFAULT_ARRIVAL_RADIUS);
+
+        let flying = matches!(field("flying"), Some(FieldValue::Bool(true)));
+
+        Self {
+            points,
+            index: 1,
+            mode,
+            forward: true,
+            waits,
+            wait: Duration::ZERO,
+            arrival_radius,
+            flying,
+        }
+    }
+}
+
+/// Items which fall out of the enemy when it dies.
+/// Taken from LDtk `loot` field
+#[derive(Component, Debug, Inspectable)]
+pub struct DropTable(pub Vec<ItemType>);
+
+/// Optional fields of the `Mob` entity which override
+///  the archetype for the specific enemy on the map
+#[derive(Debug, Default)]
+struct EnemyOverrides {
+    health: Option<i32>,
+    speed: Option<f32>,
+    damage: Option<i32>,
+    facing: Option<MovementDirection>,
+
+    /// Overrides the sight range of the behaviour
+    aggro_radius: Option<f32>,
+
+    drop_table: Vec<ItemType>,
+}
+
+/// Float fields might be declared as integers in the LDtk editor
+fn ldtk_number(value: &FieldValue) -> Option<f32> {
+    match value {
+        FieldValue::Float(value) => *value,
+        FieldValue::Int(value) => value.map(|value| value as f32),
+        _ => None,
+    }
+}
+
+impl From<&EntityInstance> for EnemyOverrides {
+    fn from(entity_instance: &EntityInstance) -> Self {
+        let mut overrides = Self {
+            drop_table: ItemType::from_ldtk_field(entity_instance, "loot"),
+            ..Default::default()
+        };
+
+        for field_instance in entity_instance.field_instances.iter() {
+            let value = &field_instance.value;
+
+            match field_instance.identifier.as_str() {
+                "health" => overrides.health = ldtk_number(value).map(|value| value as i32),
+                "speed" => overrides.speed = ldtk_number(value),
+                "damage" => overrides.damage = ldtk_number(value).map(|value| value as i32),
+                "aggro_radius" => overrides.aggro_radius = ldtk_number(value),
+                "facing" => {
+                    overrides.facing = match value {
+                        FieldValue::Enum(Some(facing)) | FieldValue::String(Some(facing)) => {
+                            match facing.as_str() {
+                                "Left" => Some(MovementDirection::Left),
+                                "Right" => Some(MovementDirection::Right),
+                                _ => {
+                                    panic!("Enemy might face only 'Left' or 'Right'");
+                                }
+                            }
+                        }
+                        _ => None,
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        overrides
+    }
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct EnemyBundle {
+    pub enemy: Enemy,
+
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+
+    #[from_entity_instance]
+    pub enemy_type: EnemyType,
+
+    #[ldtk_entity]
+    pub patrol: Patrol,
+}
+
+pub(crate) fn spawn_enemy(
+    mut commands: Commands,
+    materials: Res<GameTextures>,
+    enemies_config: Res<EnemiesConfig>,
+    progress: Res<GameProgress>,
+    enemies_query: Query<
+        (
+            Entity,
+            &Transform,
+            &EnemyType,
+            &EntityInstance,
+            Option<&Patrol>,
+        ),
+        Added<Enemy>,
+    >,
+) {
+    for (enemy, transform, enemy_type, entity_instance, patrol) in enemies_query.iter() {
+        // Killed enemies must not appear again when the level is reloaded
+        if progress.killed_enemies.contains(&entity_instance.iid) {
+            commands.entity(enemy).despawn_recursive();
+
+            continue;
+        }
+
+        let archetype = enemies_config.archetype(enemy_type);
+        let enemy_material = materials
+            .enemies
+            .get(enemy_type)
+            .get(EnemyAnimationState::Idle);
+        let overrides = EnemyOverrides::from(entity_instance);
+
+        let health = overrides.health.unwrap_or(archetype.health);
+        let direction = overrides.facing.unwrap_or(MovementDirection::Right);
+
+        let mut behaviour = enemies_config.behaviour(archetype).clone();
+
+        if let Some(aggro_radius) = overrides.aggro_radius {
+            behaviour.sight_range = aggro_radius;
+        }
+
+        // Setup a default scale for an entity. It cannot be less then 1.0 for `x` and `y` axis
+        let scale = if transform.scale.x < 1.0 || transform.scale.y < 1.0 {
+            Vec3::new(1.0, 1.0, 1.0)
+        } else {
+            transform.scale
+        };
+
+        commands
+            .entity(enemy)
+            .insert(RigidBody::Dynamic)
+            .insert(Collider::cuboid(
+                archetype.collider_size.x / 2.0,
+                archetype.collider_size.y / 2.0,
+            ))
+            .insert(LockedAxes::ROTATION_LOCKED)
+            // Draw the enemy between physics steps
+            .insert(RenderInterpolation::default())
+            .insert(Velocity::zero())
+            .insert(Friction::new(3.0))
+            .insert(ExternalImpulse::default())
+            .insert(CollisionGroups::new(ENEMY_COLLISION_GROUP, u32::MAX))
+            // The enemy looks at the side from the map. We will change it later in the system
+            .insert(direction.clone())
+            .insert(Attackable)
+            // .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(MediumAnimation {
+                timer: Timer::from_seconds(0.12, true),
+            })
+            .insert(Speed(overrides.speed.unwrap_or(archetype.speed)))
+            // By default enemy are not on move
+            .insert(OnMove(false))
+            .insert(EnemyAnimationState::Idle)
+            .insert(Health {
+                current: health,
+                max: health,
+            })
+            .insert(Damage(
+                overrides.damage.unwrap_or(archetype.attacker.damage),
+            ))
+            .insert(Knockback(archetype.attacker.knockback))
+            .insert(EnemyBehaviour::new(behaviour))
+            .insert_bundle(SpriteSheetBundle {
+                texture_atlas: enemy_material.texture.clone(),
+                sprite: TextureAtlasSprite {
+                    flip_x: direction == MovementDirection::Left,
+                    ..Default::default()
+                },
+                // transform: *transform,
+                transform: Transform {
+                    translation: transform.translation,
+                    rotation: transform.rotation,
+                    scale,
+                },
+                ..Default::default()
+            });
+
+        if let Some(projectile) = &archetype.projectile {
+            commands.entity(enemy).insert(RangedAttack {
+                projectile: projectile.clone(),
+            });
+        }
+
+        if patrol.map_or(false, |patrol| patrol.flying) {
+            commands.entity(enemy).insert(GravityScale(0.0));
+        }
+
+        if !overrides.drop_table.is_empty() {
+            commands
+                .entity(enemy)
+                .insert(DropTable(overrides.drop_table));
+        }
+    }
+}
+
+/// Starts the death animation when the health of the enemy is equal or lower than 0.
+/// The enemy stops interacting with the world and items from the drop table fall out of it
+#[allow(clippy::type_complexity)]
+pub(crate) fn dead(
+    mut commands: Commands,
+    mut progress: ResMut<GameProgress>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &Health,
+            &EntityInstance,
+            &mut EnemyAnimationState,
+            Option<(&DropTable, &GlobalTransform)>,
+        ),
+        (With<Enemy>, Changed<Health>),
+    >,
+    mut drop_item_event: EventWriter<DropItemEvent>,
+) {
+    for (enemy_entity, enemy_health, entity_instance, mut animation_state, drop) in
+        enemy_query.iter_mut()
+    {
+        if enemy_health.current <= 0 && *animation_state != EnemyAnimationState::Death {
+            progress.killed_enemies.insert(entity_instance.iid.clone());
+
+            if let Some((drop_table, enemy_transform)) = drop {
+                let enemy_translation = enemy_transform.translation();
+                let items_count = drop_table.0.len() as f32;
+
+                for (index, item) in drop_table.0.iter().enumerate() {
+                    // Place items in a row centered above the enemy
+                    let offset_x = (index as f32 - (items_count - 1.0) / 2.0) * DROPPED_ITEMS_GAP;
+
+                    drop_item_event.send(DropItemEvent {
+                        item: *item,
+                        translation: Vec3::new(
+                            enemy_translation.x + offset_x,
+                            enemy_translation.y,
+                            enemy_translation.z + 1.0,
+                        ),
+                    });
+                }
+            }
+
+            // The corpse stays in place until the death animation is over
+            *animation_state = EnemyAnimationState::Death;
+
+            commands
+                .entity(enemy_entity)
+                .remove::<EnemyBehaviour>()
+                .remove::<Attackable>()
+                .remove::<Collider>()
+                .insert(RigidBody::Fixed)
+                .insert(Velocity::zero());
+        }
+    }
+}
+
+#[cfg(test)]
+mod enemy_tests {
+    use std::time::Duration;
+
+    use bevy_ecs_ldtk::prelude::*;
+
+    use crate::common::MovementDirection;
+    use crate::items::ItemType;
+    use crate::tests::enemies::prepare_patrol;
+
+    use super::{EnemyOverrides, PatrolMode};
+
+    fn field(identifier: &str, field_type: &str, value: FieldValue) -> ldtk::FieldInstance {
+        ldtk::FieldInstance {
+            identifier: String::from(identifier),
+            tile: None,
+            field_instance_type: String::from(field_type),
+            value,
+            def_uid: 0,
+            real_editor_values: Vec::new(),
+        }
+    }
+
+    /// Advances the patrol several times and returns visited indexes
+    fn advance_patrol(mode: PatrolMode, times: usize) -> Vec<usize> {
+        let mut patrol = prepare_patrol(mode, Vec::new());
+
+        (0..times)
+            .map(|_| {
+                patrol.advance();
+                patrol.index
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_loop_the_patrol() {
+        assert_eq!(advance_patrol(PatrolMode::Loop, 5), vec![1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn should_ping_pong_the_patrol() {
+        assert_eq!(
+            advance_patrol(PatrolMode::PingPong, 6),
+            vec![1, 2, 1, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn should_stop_the_one_shot_patrol_on_the_last_point() {
+        let mut patrol = prepare_patrol(PatrolMode::OneShot, Vec::new());
+
+        assert!(patrol.advance());
+        assert!(patrol.advance());
+        assert_eq!(patrol.index, 2);
+
+        assert!(!patrol.advance());
+        assert_eq!(patrol.index, 2);
+    }
+
+    #[test]
+    fn should_wait_only_on_points_with_positive_waits() {
+        let patrol = prepare_patrol(PatrolMode::Loop, vec![0.5, -1.0]);
+
+        assert_eq!(patrol.wait_at(0), Duration::from_millis(500));
+        assert_eq!(patrol.wait_at(1), Duration::ZERO);
+        assert_eq!(patrol.wait_at(2), Duration::ZERO);
+    }
+
+    #[test]
+    fn should_override_the_archetype_by_fields_of_the_mob() {
+        let entity_instance = EntityInstance {
+            identifier: String::from("Mob"),
+            field_instances: vec![
+                field(
+                    "loot",
+                    "Array<LocalEnum.Item>",
+                    FieldValue::Enums(vec![Some(String::from("Meat")), None]),
+                ),
+                field("health", "Int", FieldValue::Int(Some(5))),
+                field("speed", "Float", FieldValue::Float(Some(40.0))),
+                field("damage", "Int", FieldValue::Int(None)),
+                field(
+                    "facing",
+                    "LocalEnum.Facing",
+                    FieldValue::Enum(Some(String::from("Left"))),
+                ),
+                field("aggro_radius", "Float", FieldValue::Float(Some(80.0))),
+            ],
+            ..Default::default()
+        };
+
+        let overrides = EnemyOverrides::from(&entity_instance);
+
+        assert_eq!(overrides.drop_table, vec![ItemType::Meat]);
+        assert_eq!(overrides.health, Some(5));
+        assert_eq!(overrides.speed, Some(40.0));
+        assert_eq!(overrides.damage, None);
+        assert_eq!(overrides.facing, Some(MovementDirection::Left));
+        assert_eq!(overrides.aggro_radius, Some(80.0));
+    }
+}
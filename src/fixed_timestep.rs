@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use bevy::{ecs::schedule::IntoSystemDescriptor, prelude::*, time::TimeSystem, utils::Instant};
+use iyes_loopless::prelude::*;
+
+use crate::actions::Action;
+
+pub struct FixedTimestepPlugin;
+
+/// Duration of one step of gameplay and physics.
+/// 64 steps per second are exact in nanoseconds and seconds (`f32`)
+///  that's why steps are not drifting against each other
+pub const FIXED_TIMESTEP: Duration = Duration::from_nanos(15_625_000);
+
+/// Label of the fixed timestep stage which runs before `CoreStage::Update`
+pub const FIXED_UPDATE: &str = "fixed_update";
+
+/// Sub-stage of `FIXED_UPDATE` for gameplay systems
+pub const GAMEPLAY_STAGE: usize = 1;
+
+/// Sub-stages of `FIXED_UPDATE` where physics applies changes of gameplay,
+///  steps the simulation and writes its results back
+pub const PHYSICS_SYNC_STAGE: usize = 2;
+pub const PHYSICS_STEP_STAGE: usize = 3;
+pub const PHYSICS_WRITEBACK_STAGE: usize = 4;
+
+/// Sub-stages which prepare and finish every step
+const BEGIN_STEP_STAGE: usize = 0;
+const END_STEP_STAGE: usize = 5;
+
+/// Forces every frame to last exactly this duration
+///  whatever the real frame rate is.
+/// Replays and tests use it to repeat the game exactly
+pub struct ForcedFrameDuration(pub Duration);
+
+/// Part of the next step which has already passed, from 0.0 to 1.0.
+/// Sprites are drawn between two last steps by this part
+#[derive(Default)]
+pub struct FixedStepProgress(pub f32);
+
+/// Actions which are seen by gameplay systems on fixed steps.
+/// Presses and releases are kept until the next step
+///  even if there are several frames between steps
+#[derive(Default)]
+struct FixedActions(Input<Action>);
+
+impl Plugin for FixedTimestepPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedActions>()
+            .init_resource::<FixedStepProgress>()
+            .add_stage_before(
+                CoreStage::Update,
+                FIXED_UPDATE,
+                FixedUpdateStage::new(FIXED_TIMESTEP, END_STEP_STAGE + 1),
+            )
+            .add_fixed_timestep_system(FIXED_UPDATE, BEGIN_STEP_STAGE, begin_fixed_actions)
+            .add_fixed_timestep_system(FIXED_UPDATE, END_STEP_STAGE, end_fixed_actions)
+            .add_system_to_stage(
+                CoreStage::First,
+                force_frame_duration
+                    .run_if_resource_exists::<ForcedFrameDuration>()
+                    .after(TimeSystem),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                buffer_fixed_actions
+                    .after("update_actions")
+                    .after("replay_actions"),
+            );
+    }
+}
+
+/// Runs its child stages once per every passed step.
+/// Unlike `FixedTimestepStage` child stages stay reachable
+///  so every plugin can add its own systems to them
+struct FixedUpdateStage {
+    step: Duration,
+    accumulator: Duration,
+    stages: Vec<SystemStage>,
+}
+
+impl FixedUpdateStage {
+    fn new(step: Duration, stages_count: usize) -> Self {
+        Self {
+            step,
+            accumulator: Duration::ZERO,
+            stages: (0..stages_count).map(|_| SystemStage::parallel()).collect(),
+        }
+    }
+}
+
+impl Stage for FixedUpdateStage {
+    fn run(&mut self, world: &mut World) {
+        self.accumulator += match world.get_resource::<Time>() {
+            Some(time) => time.delta(),
+            None => return,
+        };
+
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+
+            for stage in self.stages.iter_mut() {
+                stage.run(world);
+            }
+        }
+
+        if let Some(mut progress) = world.get_resource_mut::<FixedStepProgress>() {
+            progress.0 = self.accumulator.as_secs_f32() / self.step.as_secs_f32();
+        }
+    }
+}
+
+/// Adds gameplay systems to the child stages of the fixed timestep stage
+pub trait FixedTimestepAppExt {
+    fn add_fixed_timestep_system<Params>(
+        &mut self,
+        label: &'static str,
+        substage: usize,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self;
+
+    fn add_fixed_timestep_system_set(
+        &mut self,
+        label: &'static str,
+        substage: usize,
+        system_set: SystemSet,
+    ) -> &mut Self;
+}
+
+impl FixedTimestepAppExt for App {
+    fn add_fixed_timestep_system<Params>(
+        &mut self,
+        label: &'static str,
+        substage: usize,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self {
+        fixed_timestep_substage(self, label, substage).add_system(system);
+
+        self
+    }
+
+    fn add_fixed_timestep_system_set(
+        &mut self,
+        label: &'static str,
+        substage: usize,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        fixed_timestep_substage(self, label, substage).add_system_set(system_set);
+
+        self
+    }
+}
+
+fn fixed_timestep_substage<'a>(
+    app: &'a mut App,
+    label: &'static str,
+    substage: usize,
+) -> &'a mut SystemStage {
+    app.schedule
+        .get_stage_mut::<FixedUpdateStage>(&label)
+        .unwrap_or_else(|| panic!("Fixed timestep stage {:?} must be added", label))
+        .stages
+        .get_mut(substage)
+        .unwrap_or_else(|| {
+            panic!(
+                "Fixed timestep stage {:?} has no sub-stage {}",
+                label, substage
+            )
+        })
+}
+
+fn force_frame_duration(
+    mut time: ResMut<Time>,
+    frame_duration: Res<ForcedFrameDuration>,
+    mut forced: Local<Option<(Time, Instant)>>,
+) {
+    let (forced_time, instant) = forced.get_or_insert_with(|| {
+        let instant = Instant::now();
+        let mut forced_time = Time::default();
+
+        forced_time.update_with_instant(instant);
+
+        (forced_time, instant)
+    });
+
+    *instant += frame_duration.0;
+    forced_time.update_with_instant(*instant);
+
+    *time = forced_time.clone();
+}
+
+fn buffer_fixed_actions(actions: Res<Input<Action>>, mut fixed_actions: ResMut<FixedActions>) {
+    for action in Action::ALL {
+        // Keep both events when the action has been released
+        //  and pressed again between two steps
+        if actions.just_pressed(action) {
+            fixed_actions.0.release(action);
+        }
+
+        if actions.pressed(action) {
+            fixed_actions.0.press(action);
+        } else {
+            fixed_actions.0.release(action);
+        }
+    }
+}
+
+/// Gameplay systems read `Input<Action>` as usual
+///  but during the step it contains buffered actions
+fn begin_fixed_actions(
+    mut actions: ResMut<Input<Action>>,
+    mut fixed_actions: ResMut<FixedActions>,
+) {
+    std::mem::swap(&mut *actions, &mut fixed_actions.0);
+}
+
+/// Every press is handled only by one step
+fn end_fixed_actions(mut actions: ResMut<Input<Action>>, mut fixed_actions: ResMut<FixedActions>) {
+    std::mem::swap(&mut *actions, &mut fixed_actions.0);
+
+    fixed_actions.0.clear();
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use iyes_loopless::prelude::*;
 
-use crate::{common::Health, player::Player, ApplicationState};
+use crate::{common::Health, items::Regeneration, player::Player, ApplicationState};
 
 /// Show the Heads-up Display for the entities who have a Health component
 pub struct HudPlugin;
@@ -46,6 +46,28 @@ struct PlayerTextHud;
 #[derive(Component)]
 struct PlayerHealthBarHud;
 
+/// Returns the color of the health text. Low health is more important
+///  than regeneration so it has more priority
+fn player_health_color(health: &Health, regeneration: Option<&Regeneration>) -> Color {
+    if health.current <= 2 {
+        Color::Rgba {
+            red: 1.0,
+            green: 0.0,
+            blue: 0.0,
+            alpha: 1.0,
+        }
+    } else if regeneration.is_some() {
+        Color::Rgba {
+            red: 0.0,
+            green: 1.0,
+            blue: 0.0,
+            alpha: 1.0,
+        }
+    } else {
+        Color::WHITE
+    }
+}
+
 /// Current HuD has different sizes for each cell. Based on that
 ///  we have to hard-code values based on specific health state
 ///  this method works with health values: 0, 1, 2, 3, 4, 5
@@ -264,29 +286,49 @@ fn spawn_enemies_hud_ui(
     }
 }
 
+/// Updates the player health HuD. It's also colored when
+///  the player regenerates the health after using an item
+#[allow(clippy::type_complexity)]
 fn update_player_hud_ui(
-    health_query: Query<&Health, (Changed<Health>, With<Player>)>,
+    changed_health_query: Query<
+        (&Health, Option<&Regeneration>),
+        (With<Player>, Or<(Changed<Health>, Added<Regeneration>)>),
+    >,
+    health_query: Query<(&Health, Option<&Regeneration>), With<Player>>,
+    removed_regeneration: RemovedComponents<Regeneration>,
     mut health_ui_text_query: Query<&mut Text, With<PlayerTextHud>>,
     mut health_ui_bar_query: Query<&mut Style, With<PlayerHealthBarHud>>,
 ) {
-    for health in health_query.iter() {
+    // The regeneration might end without changing the health
+    //  but the color has to be restored anyway
+    let players = changed_health_query.iter().chain(
+        removed_regeneration
+            .iter()
+            .filter_map(|entity| health_query.get(entity).ok()),
+    );
+
+    for (health, regeneration) in players {
+        let health_value = format!("0{}", health.current);
+        let health_color = player_health_color(health, regeneration);
+
+        // Compare values before assigning them to avoid
+        //  re-rendering the text on every frame
         for mut text in health_ui_text_query.iter_mut() {
-            text.sections[0].value = format!("0{}", health.current);
+            if text.sections[0].value != health_value {
+                text.sections[0].value = health_value.clone();
+            }
 
-            if health.current <= 2 {
-                text.sections[0].style.color = Color::Rgba {
-                    red: 1.0,
-                    green: 0.0,
-                    blue: 0.0,
-                    alpha: 1.0,
-                };
-            } else {
-                text.sections[0].style.color = Color::WHITE;
+            if text.sections[0].style.color != health_color {
+                text.sections[0].style.color = health_color;
             }
         }
 
+        let health_bar_position = calculate_player_hud_shift(health);
+
         for mut bar in health_ui_bar_query.iter_mut() {
-            bar.position = calculate_player_hud_shift(health);
+            if bar.position != health_bar_position {
+                bar.position = health_bar_position;
+            }
         }
     }
 }
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{actions::Action, common::Interactable, player::Player, ApplicationState};
+
+pub struct InteractionPlugin;
+
+/// Should be fired when the player interacts with an `Interactable` entity
+/// Accepts the entity with which the player interacts
+#[derive(Debug)]
+pub struct InteractEvent(pub Entity);
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Game)
+                .with_system(interaction_detection)
+                .with_system(player_interacts)
+                .into(),
+        )
+        .add_event::<InteractEvent>();
+    }
+}
+
+/// Tracks the player which enters or leaves the `Interactable` sensors
+fn interaction_detection(
+    mut collisions: EventReader<CollisionEvent>,
+    mut interactable_query: Query<&mut Interactable>,
+    player_query: Query<With<Player>>,
+) {
+    for collision in collisions.iter() {
+        match collision {
+            CollisionEvent::Started(collider_a, collider_b, _) => {
+                if let Ok(mut interactable) = interactable_query.get_mut(*collider_a) {
+                    if player_query.get(*collider_b).is_ok() {
+                        interactable.player_nearby = true;
+                    }
+                } else if let Ok(mut interactable) = interactable_query.get_mut(*collider_b) {
+                    if player_query.get(*collider_a).is_ok() {
+                        interactable.player_nearby = true;
+                    }
+                }
+            }
+            CollisionEvent::Stopped(collider_a, collider_b, _) => {
+                if let Ok(mut interactable) = interactable_query.get_mut(*collider_a) {
+                    if player_query.get(*collider_b).is_ok() {
+                        interactable.player_nearby = false;
+                    }
+                } else if let Ok(mut interactable) = interactable_query.get_mut(*collider_b) {
+                    if player_query.get(*collider_a).is_ok() {
+                        interactable.player_nearby = false;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sends `InteractEvent` for every `Interactable` entity
+///  near the player when the player presses the interaction key
+fn player_interacts(
+    actions: Res<Input<Action>>,
+    interactable_query: Query<(Entity, &Interactable)>,
+    mut interact_event: EventWriter<InteractEvent>,
+) {
+    if !actions.just_pressed(Action::Interact) {
+        return;
+    }
+
+    for (entity, interactable) in interactable_query.iter() {
+        if interactable.player_nearby {
+            interact_event.send(InteractEvent(entity));
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    common::Interactable, interaction::InteractEvent, ron_parsers::GameTextures,
+    save::GameProgress, ApplicationState,
+};
+
+use super::item::{DropItemEvent, ItemType};
+
+pub struct ChestPlugin;
+
+/// Describes a chest which contains items
+///  and drops them when the player opens it
+#[derive(Component, Default, Debug)]
+pub struct Chest {
+    /// Items which are stored in the chest.
+    /// Taken from LDtk `content` field (or `loot` field of
+    ///  the top-down `Container`)
+    pub content: Vec<ItemType>,
+
+    /// Describes that the chest has been already opened
+    pub opened: bool,
+}
+
+impl From<EntityInstance> for Chest {
+    fn from(entity_instance: EntityInstance) -> Self {
+        let mut content = ItemType::from_ldtk_field(&entity_instance, "content");
+        content.extend(ItemType::from_ldtk_field(&entity_instance, "loot"));
+
+        Self {
+            content,
+            opened: false,
+        }
+    }
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct ChestBundle {
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+
+    #[from_entity_instance]
+    pub chest: Chest,
+}
+
+/// Horizontal gap between items which drop from the chest
+const DROPPED_ITEMS_GAP: f32 = 14.0;
+
+impl Plugin for ChestPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Game)
+                .with_system(spawn_chest)
+                .with_system(open_chest)
+                .with_system(update_chest_sprite)
+                .into(),
+        )
+        // Use the same name as it's covered in "LdtkMap"
+        .register_ldtk_entity::<ChestBundle>("Chest")
+        // The top-down map calls chests "Container"
+        .register_ldtk_entity::<ChestBundle>("Container");
+    }
+}
+
+fn spawn_chest(
+    mut commands: Commands,
+    materials: Res<GameTextures>,
+    progress: Res<GameProgress>,
+    mut chest_query: Query<(Entity, &Transform, &EntityInstance, &mut Chest), Added<Chest>>,
+) {
+    for (chest_entity, transform, entity_instance, mut chest) in chest_query.iter_mut() {
+        // Opened chests stay empty when the level is reloaded
+        if progress.opened_chests.contains(&entity_instance.iid) {
+            chest.opened = true;
+            chest.content.clear();
+        }
+
+        let width = entity_instance.width as f32;
+        let height = entity_instance.height as f32;
+
+        commands
+            .entity(chest_entity)
+            .insert(Sensor)
+            .insert(Collider::cuboid(width / 2.0, height / 2.0))
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(Interactable::default())
+            .insert_bundle(SpriteSheetBundle {
+                texture_atlas: materials.items.atlas.texture.clone(),
+                sprite: TextureAtlasSprite {
+                    index: materials.items.chest,
+                    custom_size: Some(Vec2::new(width, height)),
+                    ..Default::default()
+                },
+                transform: *transform,
+                ..Default::default()
+            });
+    }
+}
+
+/// Opens the chest when the player interacts with it
+///  and drops all its content above the chest
+fn open_chest(
+    mut progress: ResMut<GameProgress>,
+    mut interact_event: EventReader<InteractEvent>,
+    mut chest_query: Query<(&mut Chest, &GlobalTransform, &EntityInstance)>,
+    mut drop_item_event: EventWriter<DropItemEvent>,
+) {
+    for event in interact_event.iter() {
+        if let Ok((mut chest, chest_transform, entity_instance)) = chest_query.get_mut(event.0) {
+            if chest.opened {
+                continue;
+            }
+
+            chest.opened = true;
+            progress.opened_chests.insert(entity_instance.iid.clone());
+
+            let chest_translation = chest_transform.translation();
+            let items_count = chest.content.len() as f32;
+
+            for (index, item) in chest.content.drain(..).enumerate() {
+                // Place items in a row centered above the chest
+                let offset_x = (index as f32 - (items_count - 1.0) / 2.0) * DROPPED_ITEMS_GAP;
+
+                drop_item_event.send(DropItemEvent {
+                    item,
+                    translation: Vec3::new(
+                        chest_translation.x + offset_x,
+                        chest_translation.y + DROPPED_ITEMS_GAP,
+                        chest_translation.z + 1.0,
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Makes the chest a bit transparent to show that it's empty
+fn update_chest_sprite(mut chest_query: Query<(&Chest, &mut TextureAtlasSprite), Changed<Chest>>) {
+    for (chest, mut sprite) in chest_query.iter_mut() {
+        let alpha = if chest.opened { 0.5 } else { 1.0 };
+
+        if sprite.color.a() != alpha {
+            sprite.color.set_a(alpha);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{common::Health, player::Player, ron_parsers::ItemsConfig, ApplicationState};
+
+use super::inventory::{Inventory, UseItemEvent};
+
+pub struct ItemEffectsPlugin;
+
+/// Restores `amount` of health on every tick of `timer`
+///  until `duration` is finished
+#[derive(Component, Debug)]
+pub struct Regeneration {
+    pub amount: i32,
+    pub timer: Timer,
+    pub duration: Timer,
+}
+
+impl Plugin for ItemEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        // Items might be used from the inventory menu when
+        //  the game is paused. That's why we have to handle it in any state
+        app.add_system(consume_item).add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Game)
+                .with_system(regenerate_health)
+                .into(),
+        );
+    }
+}
+
+/// Takes the used item from the player inventory and applies
+///  its effect which is described in `ItemsConfig`.
+/// Items without any effect stay in the inventory
+fn consume_item(
+    mut commands: Commands,
+    items_config: Res<ItemsConfig>,
+    mut use_item_event: EventReader<UseItemEvent>,
+    mut player_query: Query<(Entity, &mut Inventory, &mut Health), With<Player>>,
+) {
+    for event in use_item_event.iter() {
+        if let Ok((player_entity, mut inventory, mut health)) = player_query.get_single_mut() {
+            let effect = match items_config.effect(event.0) {
+                Some(effect) => effect,
+                None => continue,
+            };
+
+            if !inventory.remove(event.0) {
+                continue;
+            }
+
+            if effect.heal != 0 {
+                health.current = (health.current + effect.heal).min(health.max);
+            }
+
+            if let Some(regeneration) = &effect.regeneration {
+                // The new regeneration replaces the previous one
+                commands.entity(player_entity).insert(Regeneration {
+                    amount: regeneration.amount,
+                    timer: Timer::from_seconds(regeneration.interval, true),
+                    duration: Timer::from_seconds(regeneration.duration, false),
+                });
+            }
+        }
+    }
+}
+
+fn regenerate_health(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Health, &mut Regeneration)>,
+) {
+    for (entity, mut health, mut regeneration) in query.iter_mut() {
+        regeneration.timer.tick(time.delta());
+        regeneration.duration.tick(time.delta());
+
+        // Dead entities can't regenerate
+        if regeneration.timer.just_finished() && health.current > 0 && health.current < health.max {
+            health.current = (health.current + regeneration.amount).min(health.max);
+        }
+
+        if regeneration.duration.finished() {
+            commands.entity(entity).remove::<Regeneration>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod effects_tests {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+
+    use crate::common::Health;
+    use crate::items::{Inventory, ItemType, UseItemEvent};
+    use crate::player::Player;
+    use crate::ron_parsers::ItemsConfig;
+
+    use super::{consume_item, regenerate_health, Regeneration};
+
+    /// Effects are described the same way as in `resources/items.ron`
+    fn prepare_items_config() -> ItemsConfig {
+        ron::from_str(
+            "ItemsConfig(
+                effects: [
+                    ItemEffectConfig(item: Meat, heal: 2, regeneration: None),
+                    ItemEffectConfig(
+                        item: HealingPlant,
+                        heal: 0,
+                        regeneration: Some(RegenerationConfig(
+                            amount: 1,
+                            interval: 2.0,
+                            duration: 6.0,
+                        )),
+                    ),
+                ],
+                equipment: [],
+            )",
+        )
+        .expect("Items config must be valid")
+    }
+
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        let mut time = Time::default();
+        let startup = time.startup();
+
+        // The first update only remembers the instant
+        time.update_with_instant(startup);
+
+        app.insert_resource(prepare_items_config())
+            .insert_resource(time)
+            .add_event::<UseItemEvent>()
+            .add_system(consume_item)
+            .add_system(regenerate_health);
+
+        app
+    }
+
+    fn spawn_player(app: &mut App, health: i32, items: &[ItemType]) -> Entity {
+        let mut inventory = Inventory::default();
+
+        for item in items {
+            inventory.add(*item);
+        }
+
+        app.world
+            .spawn()
+            .insert(Player)
+            .insert(Health {
+                current: health,
+                max: 5,
+            })
+            .insert(inventory)
+            .id()
+    }
+
+    fn use_item(app: &mut App, item: ItemType) {
+        app.world
+            .resource_mut::<Events<UseItemEvent>>()
+            .send(UseItemEvent(item));
+        app.update();
+    }
+
+    fn advance_time(app: &mut App, seconds: f32) {
+        let mut time = app.world.resource_mut::<Time>();
+        let last_update = time.last_update().expect("Time must be updated");
+
+        time.update_with_instant(last_update + Duration::from_secs_f32(seconds));
+        app.update();
+    }
+
+    fn health(app: &App, player_id: Entity) -> i32 {
+        app.world
+            .get::<Health>(player_id)
+            .expect("Player must have health")
+            .current
+    }
+
+    fn count(app: &App, player_id: Entity, item: ItemType) -> u32 {
+        app.world
+            .get::<Inventory>(player_id)
+            .expect("Player must have an inventory")
+            .count(item)
+    }
+
+    #[test]
+    fn should_heal_the_player_and_take_the_item() {
+        let mut app = prepare_app();
+        let player_id = spawn_player(&mut app, 2, &[ItemType::Meat, ItemType::Meat]);
+
+        use_item(&mut app, ItemType::Meat);
+
+        assert_eq!(health(&app, player_id), 4);
+        assert_eq!(count(&app, player_id, ItemType::Meat), 1);
+
+        // The health never exceeds the maximum
+        use_item(&mut app, ItemType::Meat);
+
+        assert_eq!(health(&app, player_id), 5);
+        assert_eq!(count(&app, player_id, ItemType::Meat), 0);
+    }
+
+    #[test]
+    fn should_keep_items_without_effect() {
+        let mut app = prepare_app();
+        let player_id = spawn_player(&mut app, 2, &[ItemType::Gem]);
+
+        use_item(&mut app, ItemType::Gem);
+
+        assert_eq!(health(&app, player_id), 2);
+        assert_eq!(count(&app, player_id, ItemType::Gem), 1);
+    }
+
+    #[test]
+    fn should_regenerate_the_health_until_the_duration_is_over() {
+        let mut app = prepare_app();
+        let player_id = spawn_player(&mut app, 1, &[ItemType::HealingPlant]);
+
+        use_item(&mut app, ItemType::HealingPlant);
+
+        assert_eq!(health(&app, player_id), 1);
+        assert_eq!(count(&app, player_id, ItemType::HealingPlant), 0);
+        assert!(app.world.get::<Regeneration>(player_id).is_some());
+
+        // Every tick restores the health
+        advance_time(&mut app, 2.0);
+
+        assert_eq!(health(&app, player_id), 2);
+
+        advance_time(&mut app, 1.0);
+
+        assert_eq!(health(&app, player_id), 2);
+
+        advance_time(&mut app, 1.0);
+
+        assert_eq!(health(&app, player_id), 3);
+
+        // The last tick finishes the regeneration
+        advance_time(&mut app, 2.0);
+
+        assert_eq!(health(&app, player_id), 4);
+        assert!(app.world.get::<Regeneration>(player_id).is_none());
+
+        advance_time(&mut app, 2.0);
+
+        assert_eq!(health(&app, player_id), 4);
+    }
+}
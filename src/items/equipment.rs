@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{player::Player, ron_parsers::ItemsConfig};
+
+use super::inventory::{Inventory, UseItemEvent};
+use super::item::ItemType;
+
+pub struct EquipmentPlugin;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
+pub enum EquipmentSlot {
+    Weapon,
+    Boots,
+    Armor,
+}
+
+/// Describes how an equipped item changes the entity stats.
+/// All values are added to the base entity stats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct StatModifiers {
+    /// Extra damage for every attack
+    pub damage: i32,
+
+    /// Extra movement speed
+    pub speed: f32,
+
+    /// Extra vertical jump impulse
+    pub jump: f32,
+
+    /// Reduces received damage
+    pub defense: i32,
+}
+
+impl std::ops::Add for StatModifiers {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            damage: self.damage + other.damage,
+            speed: self.speed + other.speed,
+            jump: self.jump + other.jump,
+            defense: self.defense + other.defense,
+        }
+    }
+}
+
+/// Contains items which the entity wears
+#[derive(Component, Default, Debug)]
+pub struct Equipment {
+    pub weapon: Option<ItemType>,
+    pub boots: Option<ItemType>,
+    pub armor: Option<ItemType>,
+
+    /// Sum of modifiers of all equipped items.
+    /// Recalculated every time when equipment changes
+    pub modifiers: StatModifiers,
+}
+
+impl Equipment {
+    pub fn slot(&self, slot: EquipmentSlot) -> Option<ItemType> {
+        match slot {
+            EquipmentSlot::Weapon => self.weapon,
+            EquipmentSlot::Boots => self.boots,
+            EquipmentSlot::Armor => self.armor,
+        }
+    }
+
+    fn slot_mut(&mut self, slot: EquipmentSlot) -> &mut Option<ItemType> {
+        match slot {
+            EquipmentSlot::Weapon => &mut self.weapon,
+            EquipmentSlot::Boots => &mut self.boots,
+            EquipmentSlot::Armor => &mut self.armor,
+        }
+    }
+
+    /// Puts the item into the slot.
+    /// Returns the item which has been in this slot before
+    pub fn equip(
+        &mut self,
+        slot: EquipmentSlot,
+        item: ItemType,
+        items_config: &ItemsConfig,
+    ) -> Option<ItemType> {
+        let previous_item = self.slot_mut(slot).replace(item);
+        self.recalculate_modifiers(items_config);
+
+        previous_item
+    }
+
+    /// Makes the slot empty. Returns the item which has been in this slot
+    pub fn unequip(&mut self, slot: EquipmentSlot, items_config: &ItemsConfig) -> Option<ItemType> {
+        let previous_item = self.slot_mut(slot).take();
+        self.recalculate_modifiers(items_config);
+
+        previous_item
+    }
+
+    fn recalculate_modifiers(&mut self, items_config: &ItemsConfig) {
+        self.modifiers = [self.weapon, self.boots, self.armor]
+            .iter()
+            .flatten()
+            .filter_map(|item| items_config.equipment(*item))
+            .fold(StatModifiers::default(), |modifiers, equipment| {
+                modifiers + equipment.modifiers
+            });
+    }
+}
+
+/// Should be fired when the player takes off the item from the slot
+/// Accepts the slot which should be empty
+#[derive(Debug)]
+pub struct UnequipItemEvent(pub EquipmentSlot);
+
+impl Plugin for EquipmentPlugin {
+    fn build(&self, app: &mut App) {
+        // Items might be equipped from the inventory menu when
+        //  the game is paused. That's why we have to handle it in any state
+        app.add_system(equip_item)
+            .add_system(unequip_item)
+            .add_event::<UnequipItemEvent>();
+    }
+}
+
+/// Moves the used item from the inventory into the equipment slot.
+/// The item which has been in this slot goes back to the inventory
+fn equip_item(
+    items_config: Res<ItemsConfig>,
+    mut use_item_event: EventReader<UseItemEvent>,
+    mut player_query: Query<(&mut Inventory, &mut Equipment), With<Player>>,
+) {
+    for event in use_item_event.iter() {
+        if let Ok((mut inventory, mut equipment)) = player_query.get_single_mut() {
+            let equipment_config = match items_config.equipment(event.0) {
+                Some(equipment_config) => equipment_config,
+                None => continue,
+            };
+
+            if !inventory.remove(event.0) {
+                continue;
+            }
+
+            if let Some(previous_item) =
+                equipment.equip(equipment_config.slot, event.0, &items_config)
+            {
+                inventory.add(previous_item);
+            }
+        }
+    }
+}
+
+fn unequip_item(
+    items_config: Res<ItemsConfig>,
+    mut unequip_item_event: EventReader<UnequipItemEvent>,
+    mut player_query: Query<(&mut Inventory, &mut Equipment), With<Player>>,
+) {
+    for event in unequip_item_event.iter() {
+        if let Ok((mut inventory, mut equipment)) = player_query.get_single_mut() {
+            if let Some(item) = equipment.unequip(event.0, &items_config) {
+                inventory.add(item);
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{player::Player, ApplicationState};
+
+use super::item::{DroppedItem, ItemType};
+
+pub struct InventoryPlugin;
+
+/// Describes a stack of the same items
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ItemStack {
+    pub item: ItemType,
+    pub count: u32,
+}
+
+/// Contains all items which the entity carries
+#[derive(Component, Default, Debug, Clone)]
+pub struct Inventory {
+    /// Stacks are kept in the order in which the items have been picked up
+    pub stacks: Vec<ItemStack>,
+}
+
+impl Inventory {
+    /// Puts the item into the existing stack or creates a new one
+    pub fn add(&mut self, item: ItemType) {
+        if let Some(stack) = self.stacks.iter_mut().find(|stack| stack.item == item) {
+            stack.count += 1;
+        } else {
+            self.stacks.push(ItemStack { item, count: 1 });
+        }
+    }
+
+    /// Takes one item from the stack. Removes the stack when it's empty.
+    /// Returns `false` if there is no such item in the inventory
+    pub fn remove(&mut self, item: ItemType) -> bool {
+        if let Some(index) = self.stacks.iter().position(|stack| stack.item == item) {
+            self.stacks[index].count -= 1;
+
+            if self.stacks[index].count == 0 {
+                self.stacks.remove(index);
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Returns how many items of the specific type the inventory contains
+    #[cfg(test)]
+    pub fn count(&self, item: ItemType) -> u32 {
+        self.stacks
+            .iter()
+            .find(|stack| stack.item == item)
+            .map_or(0, |stack| stack.count)
+    }
+}
+
+/// The player starts with items from LDtk `items` field
+impl From<EntityInstance> for Inventory {
+    fn from(entity_instance: EntityInstance) -> Self {
+        let mut inventory = Self::default();
+
+        for item in ItemType::from_ldtk_field(&entity_instance, "items") {
+            inventory.add(item);
+        }
+
+        inventory
+    }
+}
+
+/// Should be fired when the player uses an item from the inventory
+/// Accepts the item which should be used
+#[derive(Debug)]
+pub struct UseItemEvent(pub ItemType);
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(pickup_item.run_in_state(ApplicationState::Game))
+            .add_event::<UseItemEvent>();
+    }
+}
+
+/// Puts the dropped item into the player inventory when the player touches it
+fn pickup_item(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+    dropped_item_query: Query<&DroppedItem>,
+) {
+    for collision in collisions.iter() {
+        if let CollisionEvent::Started(collider_a, collider_b, _) = collision {
+            let (item_entity, player_entity) = if dropped_item_query.get(*collider_a).is_ok() {
+                (*collider_a, *collider_b)
+            } else if dropped_item_query.get(*collider_b).is_ok() {
+                (*collider_b, *collider_a)
+            } else {
+                continue;
+            };
+
+            if let Ok(mut inventory) = player_query.get_mut(player_entity) {
+                let dropped_item = dropped_item_query
+                    .get(item_entity)
+                    .expect("Dropped item must exist");
+
+                inventory.add(dropped_item.0);
+
+                commands.entity(item_entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod inventory_tests {
+    use crate::items::ItemType;
+
+    use super::{Inventory, ItemStack};
+
+    #[test]
+    fn should_stack_the_same_items() {
+        let mut inventory = Inventory::default();
+
+        inventory.add(ItemType::Meat);
+        inventory.add(ItemType::Water);
+        inventory.add(ItemType::Meat);
+
+        assert_eq!(inventory.count(ItemType::Meat), 2);
+        assert_eq!(inventory.count(ItemType::Water), 1);
+        assert_eq!(inventory.count(ItemType::Gem), 0);
+
+        // Stacks are kept in the order of the pickup
+        assert_eq!(
+            inventory.stacks,
+            vec![
+                ItemStack {
+                    item: ItemType::Meat,
+                    count: 2,
+                },
+                ItemStack {
+                    item: ItemType::Water,
+                    count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_remove_the_stack_when_it_is_empty() {
+        let mut inventory = Inventory::default();
+
+        inventory.add(ItemType::Meat);
+        inventory.add(ItemType::Meat);
+
+        assert!(inventory.remove(ItemType::Meat));
+        assert_eq!(inventory.count(ItemType::Meat), 1);
+
+        assert!(inventory.remove(ItemType::Meat));
+        assert_eq!(inventory.count(ItemType::Meat), 0);
+        assert!(inventory.stacks.is_empty());
+    }
+
+    #[test]
+    fn should_not_remove_missing_items() {
+        let mut inventory = Inventory::default();
+
+        inventory.add(ItemType::Water);
+
+        assert!(!inventory.remove(ItemType::Meat));
+        assert_eq!(inventory.count(ItemType::Water), 1);
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_inspector_egui::Inspectable;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ron_parsers::GameTextures, ApplicationState};
+
+pub struct ItemPlugin;
+
+/// Describes all items which might be found in the game
+/// Covers the LDtk `Item` enum of both maps
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Inspectable, Deserialize, Serialize)]
+pub enum ItemType {
+    Knife,
+    HealingPlant,
+    Meat,
+    Boots,
+    Water,
+    Gem,
+
+    // Items of the top-down map
+    Scraps,
+    Battery,
+    Food,
+    Medkit,
+    PowerOrb,
+    Key,
+}
+
+impl Default for ItemType {
+    fn default() -> Self {
+        Self::Gem
+    }
+}
+
+impl ItemType {
+    /// Converts the LDtk `Item` enum value into the `ItemType`
+    pub fn from_ldtk(value: &str) -> Self {
+        match value {
+            "Knife" => Self::Knife,
+            "Healing_Plant" => Self::HealingPlant,
+            "Meat" => Self::Meat,
+            "Boots" => Self::Boots,
+            "Water" => Self::Water,
+            "Gem" => Self::Gem,
+            "Scraps" => Self::Scraps,
+            "Battery" => Self::Battery,
+            "Food" => Self::Food,
+            // `Health` name is already taken by the component
+            "Health" => Self::Medkit,
+            "PowerOrb" => Self::PowerOrb,
+            "Key" => Self::Key,
+            _ => {
+                panic!("This is impossible option");
+            }
+        }
+    }
+
+    /// Reads the whole array of `Item` enums from the LDtk field.
+    /// Returns an empty list if the entity has no such field
+    pub fn from_ldtk_field(entity_instance: &EntityInstance, identifier: &str) -> Vec<Self> {
+        let field_instance = entity_instance
+            .field_instances
+            .iter()
+            .find(|field| field.identifier == *identifier);
+
+        match field_instance.map(|field| &field.value) {
+            Some(FieldValue::Enums(values)) => values
+                .iter()
+                .flatten()
+                .map(|value| Self::from_ldtk(value))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns a human readable name of the item
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Knife => "Knife",
+            Self::HealingPlant => "Healing plant",
+            Self::Meat => "Meat",
+            Self::Boots => "Boots",
+            Self::Water => "Water",
+            Self::Gem => "Gem",
+            Self::Scraps => "Scraps",
+            Self::Battery => "Battery",
+            Self::Food => "Food",
+            Self::Medkit => "Medkit",
+            Self::PowerOrb => "Power orb",
+            Self::Key => "Key",
+        }
+    }
+
+    /// Returns an index of the item icon in the items sheet
+    pub fn sprite_index(&self, materials: &GameTextures) -> usize {
+        match self {
+            Self::Knife => materials.items.knife,
+            Self::HealingPlant => materials.items.healing_plant,
+            Self::Meat => materials.items.meat,
+            Self::Boots => materials.items.boots,
+            Self::Water => materials.items.water,
+            Self::Gem => materials.items.gem,
+            Self::Scraps => materials.items.scraps,
+            Self::Battery => materials.items.battery,
+            Self::Food => materials.items.food,
+            Self::Medkit => materials.items.medkit,
+            Self::PowerOrb => materials.items.power_orb,
+            Self::Key => materials.items.key,
+        }
+    }
+}
+
+/// Describes an item which lies in the world
+#[derive(Component, Debug, Inspectable)]
+pub struct DroppedItem(pub ItemType);
+
+/// Should be fired when an item has to appear in the world
+///  (e.g. from the opened chest)
+#[derive(Debug)]
+pub struct DropItemEvent {
+    pub item: ItemType,
+
+    /// World coordinates where the item should be placed
+    pub translation: Vec3,
+}
+
+/// Size of the dropped item in the world. Icons are 32x32 pixels
+///  which is too big for 16x16 tiles
+const DROPPED_ITEM_SIZE: f32 = 16.0;
+
+impl Plugin for ItemPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(spawn_dropped_item.run_in_state(ApplicationState::Game))
+            .add_event::<DropItemEvent>();
+    }
+}
+
+fn spawn_dropped_item(
+    mut commands: Commands,
+    materials: Res<GameTextures>,
+    mut drop_item_event: EventReader<DropItemEvent>,
+) {
+    for event in drop_item_event.iter() {
+        commands
+            .spawn_bundle(SpriteSheetBundle {
+                texture_atlas: materials.items.atlas.texture.clone(),
+                sprite: TextureAtlasSprite {
+                    index: event.item.sprite_index(&materials),
+                    custom_size: Some(Vec2::splat(DROPPED_ITEM_SIZE)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(event.translation),
+                ..Default::default()
+            })
+            .insert(Sensor)
+            .insert(Collider::cuboid(
+                DROPPED_ITEM_SIZE / 2.0,
+                DROPPED_ITEM_SIZE / 2.0,
+            ))
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(DroppedItem(event.item));
+    }
+}
@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+mod chest;
+mod effects;
+mod equipment;
+mod inventory;
+mod item;
+
+pub use effects::Regeneration;
+pub use equipment::{Equipment, EquipmentSlot, StatModifiers, UnequipItemEvent};
+pub use inventory::{Inventory, ItemStack, UseItemEvent};
+pub use item::{DropItemEvent, DroppedItem, ItemType};
+
+pub struct ItemsPlugin;
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(item::ItemPlugin)
+            .add_plugin(chest::ChestPlugin)
+            .add_plugin(inventory::InventoryPlugin)
+            .add_plugin(effects::ItemEffectsPlugin)
+            .add_plugin(equipment::EquipmentPlugin);
+    }
+}
@@ -1,29 +1,41 @@
 use bevy::{app::AppExit, prelude::*};
 use bevy_ecs_ldtk::prelude::*;
-use bevy_rapier2d::plugin::RapierConfiguration;
+use bevy_rapier2d::prelude::*;
 use iyes_loopless::prelude::*;
 
 use crate::{
-    player::{PlayerAnimationState, PlayerProcessAnimation},
-    ApplicationState, ApplicationStateMenu, ExitTheGameEvent, PauseTheGameEvent,
-    ResumeTheGameEvent,
+    actions::Action,
+    checkpoint::{spawn_dead_player, ActiveCheckpoint},
+    common::Health,
+    level_transition::{LevelEntry, LevelTransition, TransitionFade},
+    physics::rapier_configuration,
+    player::{DeadPlayer, Player, PlayerAnimationState, PlayerProcessAnimation},
+    save::GameProgress,
+    ui::InventoryOpenedInGame,
+    ApplicationState, ApplicationStateMenu, ExitTheGameEvent, GameMode, OpenInventoryEvent,
+    PauseTheGameEvent, RestartLevelEvent, ResumeTheGameEvent,
 };
 
 pub struct GameLdtkPlugin;
 
+/// Exists while the main menu is opened by pausing the game
+///  (not on the start of the application)
+pub struct GamePaused;
+
+/// The level where the game starts
+const START_LEVEL: LevelSelection = LevelSelection::Uid(0);
+
 impl Plugin for GameLdtkPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(LdtkPlugin)
-            .insert_resource(LevelSelection::Uid(0))
-            .insert_resource(LdtkSettings {
-                level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
-                    load_level_neighbors: true,
-                },
-                ..Default::default()
-            })
+            .insert_resource(START_LEVEL)
+            .insert_resource(ldtk_settings())
             .add_startup_system(setup)
             .add_system(keyboard_state_changer)
             .add_system(change_game_state)
+            // The level is restarted from menus
+            //  that's why we have to handle it in any state
+            .add_system(restart_level)
             .add_enter_system(
                 PlayerAnimationState::Death(PlayerProcessAnimation::End),
                 show_dead_menu,
@@ -31,29 +43,66 @@ impl Plugin for GameLdtkPlugin {
     }
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Neighbour levels are spawned at their places in the world
+///  to let the player walk from one level to another
+fn ldtk_settings() -> LdtkSettings {
+    LdtkSettings {
+        level_spawn_behavior: LevelSpawnBehavior::UseWorldTranslation {
+            load_level_neighbors: true,
+        },
+        ..Default::default()
+    }
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, game_mode: Res<GameMode>) {
     asset_server.watch_for_changes().unwrap();
 
     commands.spawn_bundle(LdtkWorldBundle {
-        ldtk_handle: asset_server.load("Typical_2D_platformer_wip.ldtk"),
+        ldtk_handle: asset_server.load(game_mode.map_path()),
         ..Default::default()
     });
 }
 
+#[allow(clippy::too_many_arguments)]
 fn keyboard_state_changer(
+    mut commands: Commands,
     app_state: Res<CurrentState<ApplicationState>>,
+    game_paused: Option<Res<GamePaused>>,
+    opened_in_game: Option<Res<InventoryOpenedInGame>>,
     mut pause_game_event: EventWriter<PauseTheGameEvent>,
     mut resume_game_event: EventWriter<ResumeTheGameEvent>,
-    keyboard: Res<Input<KeyCode>>,
+    mut open_inventory_event: EventWriter<OpenInventoryEvent>,
+    actions: Res<Input<Action>>,
 ) {
-    if keyboard.just_pressed(KeyCode::Escape) {
+    // Other menus are left by `Action::MenuBack` which presses their back button
+    if actions.just_pressed(Action::Pause) {
         match app_state.0 {
             ApplicationState::Game => {
                 pause_game_event.send(PauseTheGameEvent);
             }
-            ApplicationState::Menu(_) => {
+            ApplicationState::Menu(ApplicationStateMenu::Main) if game_paused.is_some() => {
                 resume_game_event.send(ResumeTheGameEvent);
             }
+            ApplicationState::Menu(_) => {}
+        }
+    }
+
+    if actions.just_pressed(Action::Inventory) {
+        match app_state.0 {
+            ApplicationState::Game => {
+                open_inventory_event.send(OpenInventoryEvent);
+            }
+            // The inventory is closed the same way as its back button does
+            ApplicationState::Menu(ApplicationStateMenu::Inventory) => {
+                if opened_in_game.is_some() {
+                    resume_game_event.send(ResumeTheGameEvent);
+                } else {
+                    commands.insert_resource(NextState(ApplicationState::Menu(
+                        ApplicationStateMenu::Main,
+                    )));
+                }
+            }
+            ApplicationState::Menu(_) => {}
         }
     }
 }
@@ -73,15 +122,28 @@ fn change_game_state(
     mut commands: Commands,
     mut pause_game_event: EventReader<PauseTheGameEvent>,
     mut resume_game_event: EventReader<ResumeTheGameEvent>,
+    mut open_inventory_event: EventReader<OpenInventoryEvent>,
     mut exit_game_event: EventReader<ExitTheGameEvent>,
     mut rapier_config: ResMut<RapierConfiguration>,
     mut exit: EventWriter<AppExit>,
     current_state: Res<CurrentState<ApplicationState>>,
+    level_transition: Option<Res<LevelTransition>>,
 ) {
     for _ in pause_game_event.iter() {
         commands.insert_resource(NextState(ApplicationState::Menu(
             ApplicationStateMenu::Main,
         )));
+        commands.insert_resource(GamePaused);
+
+        // Turn off the physics when we pause the game
+        rapier_config.physics_pipeline_active = false;
+    }
+
+    for _ in open_inventory_event.iter() {
+        commands.insert_resource(NextState(ApplicationState::Menu(
+            ApplicationStateMenu::Inventory,
+        )));
+        commands.insert_resource(InventoryOpenedInGame);
 
         // Turn off the physics when we pause the game
         rapier_config.physics_pipeline_active = false;
@@ -103,12 +165,439 @@ fn change_game_state(
         }
 
         commands.insert_resource(NextState(ApplicationState::Game));
+        commands.remove_resource::<GamePaused>();
 
-        // Turn on the physics when we resume the game
-        rapier_config.physics_pipeline_active = true;
+        // Turn on the physics when we resume the game.
+        // The level transition turns it on by itself when the screen fades in
+        if level_transition.is_none() {
+            rapier_config.physics_pipeline_active = true;
+        }
     }
 
     for _ in exit_game_event.iter() {
         exit.send(AppExit);
     }
 }
+
+/// Re-creates the LDtk world from scratch. All enemies and chests
+///  are spawned by LDtk again as on the first start.
+/// Kept entities (e.g. the player) are moved into the new world.
+/// LDtk doesn't spawn `Worldly` entities again if they still exist
+pub fn respawn_world(
+    commands: &mut Commands,
+    world_query: &Query<(Entity, &Handle<LdtkAsset>)>,
+    kept_entities: &[Entity],
+) {
+    for (world_entity, ldtk_handle) in world_query.iter() {
+        let mut world_commands = commands.entity(world_entity);
+
+        world_commands.remove_children(kept_entities);
+        world_commands.despawn_recursive();
+
+        commands
+            .spawn_bundle(LdtkWorldBundle {
+                ldtk_handle: ldtk_handle.clone(),
+                ..Default::default()
+            })
+            .push_children(kept_entities);
+    }
+}
+
+/// Restarts the current level from the place where the player has entered it.
+/// Enemies and chests are spawned by LDtk again
+// Allow too many arguments for specific function
+#[allow(clippy::too_many_arguments)]
+fn restart_level(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut restart_level_event: EventReader<RestartLevelEvent>,
+    level_entry: Option<Res<LevelEntry>>,
+    dead_player: Option<Res<DeadPlayer>>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    world_query: Query<(Entity, &Handle<LdtkAsset>)>,
+    mut player_query: Query<
+        (Entity, &mut Transform, &mut Health, Option<&mut Velocity>),
+        With<Player>,
+    >,
+    fade_query: Query<Entity, With<TransitionFade>>,
+) {
+    if restart_level_event.iter().next().is_none() {
+        return;
+    }
+
+    // LDtk doesn't spawn the `Worldly` player again
+    //  that's why it's moved into the new world
+    let mut player_entities: Vec<Entity> = player_query.iter().map(|(entity, ..)| entity).collect();
+
+    if let Some(level_entry) = level_entry {
+        // The level might be changed by the unfinished level transition
+        *level_selection = level_entry.level.clone();
+
+        for (_, mut transform, mut health, velocity) in player_query.iter_mut() {
+            transform.translation = level_entry.translation;
+            health.current = health.max;
+
+            if let Some(mut velocity) = velocity {
+                *velocity = Velocity::zero();
+            }
+        }
+
+        if let Some(dead_player) = dead_player {
+            player_entities.push(spawn_dead_player(
+                &mut commands,
+                &dead_player,
+                level_entry.translation,
+            ));
+        }
+
+        // Checkpoints of the level have to be reached again
+        commands.insert_resource(ActiveCheckpoint {
+            level: level_entry.level.clone(),
+            translation: level_entry.translation,
+        });
+    } else {
+        commands.remove_resource::<ActiveCheckpoint>();
+    }
+
+    respawn_world(&mut commands, &world_query, &player_entities);
+
+    // Stop the level transition if the player has paused the game during it
+    for fade_entity in fade_query.iter() {
+        commands.entity(fade_entity).despawn_recursive();
+    }
+    commands.remove_resource::<LevelTransition>();
+
+    // Forget everything the player has done on the map
+    commands.insert_resource(GameProgress::default());
+    commands.remove_resource::<DeadPlayer>();
+    commands.remove_resource::<GamePaused>();
+
+    *rapier_config = rapier_configuration(*game_mode);
+
+    commands.insert_resource(NextState(PlayerAnimationState::Idle));
+    commands.insert_resource(NextState(ApplicationState::Game));
+}
+
+#[cfg(test)]
+mod ldtk_tests {
+    use std::time::Duration;
+
+    use bevy::asset::AssetPlugin;
+    use bevy::ecs::event::Events;
+    use bevy::prelude::*;
+    use bevy_ecs_ldtk::app::{LdtkEntityMap, LdtkIntCellMap};
+    use bevy_ecs_ldtk::prelude::*;
+    use bevy_ecs_ldtk::systems::{
+        apply_level_selection, apply_level_set, process_ldtk_levels, worldly_adoption,
+    };
+    use bevy_ecs_ldtk::{LdtkLevel, LdtkLevelLoader, LdtkLoader, LevelEvent};
+    use bevy_rapier2d::prelude::*;
+    use iyes_loopless::prelude::*;
+
+    use crate::actions::Action;
+    use crate::common::Health;
+    use crate::enemy::Enemy;
+    use crate::items::Inventory;
+    use crate::level_transition::LevelEntry;
+    use crate::player::{DeadPlayer, Player, PlayerAnimationState};
+    use crate::save::GameProgress;
+    use crate::ui::InventoryOpenedInGame;
+    use crate::{
+        ApplicationState, ApplicationStateMenu, GameMode, OpenInventoryEvent, PauseTheGameEvent,
+        RestartLevelEvent, ResumeTheGameEvent,
+    };
+
+    use super::{keyboard_state_changer, ldtk_settings, restart_level};
+
+    const MAP_PATH: &str = "Typical_2D_platformer_wip.ldtk";
+
+    /// The level above the start level
+    const TOP_LEVEL_IID: &str = "a316bd80-66b0-11ec-9cd7-c50cdc9d2cc4";
+
+    /// One of enemies on the top level
+    const TOP_ENEMY_IID: &str = "a316bd86-66b0-11ec-9cd7-837d4383ebc8";
+
+    #[derive(Bundle, LdtkEntity)]
+    struct TestPlayerBundle {
+        player: Player,
+        health: Health,
+
+        #[worldly]
+        worldly: Worldly,
+
+        #[from_entity_instance]
+        entity_instance: EntityInstance,
+    }
+
+    #[derive(Bundle, LdtkEntity)]
+    struct TestEnemyBundle {
+        enemy: Enemy,
+
+        #[from_entity_instance]
+        entity_instance: EntityInstance,
+    }
+
+    /// Spawns the real map by LDtk systems.
+    /// `LdtkPlugin` can't be used because its tilemap rendering needs the render app
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<LdtkAsset>()
+            .init_asset_loader::<LdtkLoader>()
+            .add_asset::<LdtkLevel>()
+            .init_asset_loader::<LdtkLevelLoader>()
+            .init_non_send_resource::<LdtkEntityMap>()
+            .init_non_send_resource::<LdtkIntCellMap>()
+            .init_resource::<ClearColor>()
+            .insert_resource(ldtk_settings())
+            .add_event::<LevelEvent>()
+            .add_system_to_stage(CoreStage::PreUpdate, process_ldtk_levels)
+            .add_system_to_stage(CoreStage::PostUpdate, worldly_adoption)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                apply_level_selection.label("level_selection"),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                apply_level_set.after("level_selection"),
+            )
+            .register_ldtk_entity::<TestPlayerBundle>("Player")
+            .register_ldtk_entity::<TestEnemyBundle>("Mob")
+            .insert_resource(GameMode::Platformer)
+            .insert_resource(LevelSelection::Uid(0))
+            .insert_resource(RapierConfiguration {
+                physics_pipeline_active: false,
+                ..Default::default()
+            })
+            .insert_resource(GameProgress::default())
+            .add_event::<RestartLevelEvent>()
+            .add_system(restart_level);
+
+        let ldtk_handle = app.world.resource::<AssetServer>().load(MAP_PATH);
+
+        app.world.spawn().insert_bundle(LdtkWorldBundle {
+            ldtk_handle,
+            ..Default::default()
+        });
+
+        app
+    }
+
+    /// The map is loaded in the background that's why
+    ///  we have to wait until LDtk spawns the expected entities
+    fn update_until(app: &mut App, condition: impl Fn(&mut App) -> bool) {
+        for _ in 0..500 {
+            app.update();
+
+            if condition(app) {
+                return;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        panic!("LDtk hasn't spawned expected entities");
+    }
+
+    fn players(app: &mut App) -> Vec<Entity> {
+        app.world
+            .query_filtered::<Entity, With<Player>>()
+            .iter(&app.world)
+            .collect()
+    }
+
+    fn enemies_with_iid(app: &mut App, iid: &str) -> Vec<Entity> {
+        app.world
+            .query_filtered::<(Entity, &EntityInstance), With<Enemy>>()
+            .iter(&app.world)
+            .filter(|(_, entity_instance)| entity_instance.iid == iid)
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    /// The player has come to the top level and killed the enemy there
+    fn play_on_top_level(app: &mut App, level_entry: Vec3) -> Entity {
+        update_until(app, |app| {
+            players(app).len() == 1 && enemies_with_iid(app, TOP_ENEMY_IID).len() == 1
+        });
+
+        let player_entity = players(app)[0];
+
+        *app.world.resource_mut::<LevelSelection>() =
+            LevelSelection::Iid(String::from(TOP_LEVEL_IID));
+        app.insert_resource(LevelEntry {
+            level: LevelSelection::Iid(String::from(TOP_LEVEL_IID)),
+            translation: level_entry,
+        });
+
+        let mut player = app.world.entity_mut(player_entity);
+
+        player.get_mut::<Transform>().unwrap().translation = Vec3::new(500.0, 300.0, 1.0);
+        player.insert(Health { current: 1, max: 5 });
+
+        let enemy_entity = enemies_with_iid(app, TOP_ENEMY_IID)[0];
+        app.world.entity_mut(enemy_entity).despawn_recursive();
+        app.world
+            .resource_mut::<GameProgress>()
+            .killed_enemies
+            .insert(String::from(TOP_ENEMY_IID));
+
+        app.update();
+
+        player_entity
+    }
+
+    fn restart(app: &mut App) {
+        app.world
+            .resource_mut::<Events<RestartLevelEvent>>()
+            .send(RestartLevelEvent);
+
+        app.update();
+    }
+
+    #[test]
+    fn should_restart_the_current_level() {
+        let mut app = prepare_app();
+        let level_entry = Vec3::new(100.0, 200.0, 1.0);
+
+        let player_entity = play_on_top_level(&mut app, level_entry);
+
+        restart(&mut app);
+
+        // LDtk spawns the killed enemy again
+        update_until(&mut app, |app| {
+            enemies_with_iid(app, TOP_ENEMY_IID).len() == 1
+        });
+
+        assert_eq!(
+            *app.world.resource::<LevelSelection>(),
+            LevelSelection::Iid(String::from(TOP_LEVEL_IID))
+        );
+        assert!(app
+            .world
+            .resource::<GameProgress>()
+            .killed_enemies
+            .is_empty());
+
+        // The same player is moved to the level entry with the full health
+        assert_eq!(players(&mut app), vec![player_entity]);
+
+        let player = app.world.entity(player_entity);
+
+        assert_eq!(player.get::<Transform>().unwrap().translation, level_entry);
+        assert_eq!(player.get::<Health>().unwrap().current, 5);
+    }
+
+    #[test]
+    fn should_restore_the_dead_player_at_the_level_entry() {
+        let mut app = prepare_app();
+        let level_entry = Vec3::new(100.0, 200.0, 1.0);
+
+        let player_entity = play_on_top_level(&mut app, level_entry);
+
+        // The player is removed when the death animation is over
+        let player = app.world.entity(player_entity);
+        let dead_player = DeadPlayer {
+            worldly: player.get::<Worldly>().unwrap().clone(),
+            entity_instance: player.get::<EntityInstance>().unwrap().clone(),
+            inventory: Inventory::default(),
+        };
+
+        app.world.entity_mut(player_entity).despawn_recursive();
+        app.insert_resource(dead_player);
+
+        restart(&mut app);
+
+        update_until(&mut app, |app| {
+            enemies_with_iid(app, TOP_ENEMY_IID).len() == 1
+        });
+
+        // LDtk doesn't spawn one more player when the start level is spawned again
+        let restored_players = players(&mut app);
+
+        assert_eq!(restored_players.len(), 1);
+        assert_eq!(
+            app.world
+                .get::<Transform>(restored_players[0])
+                .unwrap()
+                .translation,
+            level_entry
+        );
+        assert!(app.world.get_resource::<DeadPlayer>().is_none());
+    }
+
+    #[test]
+    fn should_reset_physics_and_return_to_the_game() {
+        let mut app = prepare_app();
+
+        restart(&mut app);
+
+        let rapier_config = app.world.resource::<RapierConfiguration>();
+
+        assert!(rapier_config.physics_pipeline_active);
+        assert_eq!(rapier_config.gravity, Vec2::new(0.0, -300.0));
+        assert_eq!(
+            app.world.resource::<NextState<ApplicationState>>().0,
+            ApplicationState::Game
+        );
+        assert_eq!(
+            app.world.resource::<NextState<PlayerAnimationState>>().0,
+            PlayerAnimationState::Idle
+        );
+    }
+
+    /// Prepares the app where the inventory menu is opened
+    ///  and the inventory key has just been pressed
+    fn prepare_inventory_key_app() -> App {
+        let mut app = App::new();
+        let mut actions = Input::<Action>::default();
+
+        actions.press(Action::Inventory);
+
+        app.insert_resource(actions)
+            .add_loopless_state(ApplicationState::Menu(ApplicationStateMenu::Inventory))
+            .add_event::<PauseTheGameEvent>()
+            .add_event::<ResumeTheGameEvent>()
+            .add_event::<OpenInventoryEvent>()
+            .add_system(keyboard_state_changer);
+
+        app
+    }
+
+    #[test]
+    fn should_resume_the_game_by_the_inventory_key_if_opened_in_game() {
+        let mut app = prepare_inventory_key_app();
+
+        app.insert_resource(InventoryOpenedInGame);
+        app.update();
+
+        let resume_events = app.world.resource::<Events<ResumeTheGameEvent>>();
+
+        assert_eq!(resume_events.len(), 1);
+        assert!(app
+            .world
+            .get_resource::<NextState<ApplicationState>>()
+            .is_none());
+    }
+
+    #[test]
+    fn should_return_to_the_main_menu_by_the_inventory_key_if_opened_from_it() {
+        let mut app = prepare_inventory_key_app();
+
+        app.update();
+
+        let resume_events = app.world.resource::<Events<ResumeTheGameEvent>>();
+
+        assert!(resume_events.is_empty());
+        assert_eq!(
+            app.world.resource::<NextState<ApplicationState>>().0,
+            ApplicationState::Menu(ApplicationStateMenu::Main)
+        );
+    }
+}
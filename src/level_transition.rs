@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{checkpoint::ActiveCheckpoint, player::Player, ApplicationState};
+
+pub struct LevelTransitionPlugin;
+
+/// Should be fired when the player has to be moved into another level
+/// Accepts the level and the entity in this level where the player should appear
+#[derive(Debug, Clone)]
+pub struct LevelTransitionEvent {
+    pub level_iid: String,
+    pub entity_iid: String,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum TransitionStage {
+    /// The screen becomes black
+    FadeOut,
+
+    /// The screen is black and we are waiting for the destination entity
+    Loading,
+
+    /// The screen becomes visible again
+    FadeIn,
+}
+
+/// Exists only while the level transition is in progress
+pub struct LevelTransition {
+    destination: LevelTransitionEvent,
+    stage: TransitionStage,
+    timer: Timer,
+}
+
+/// Describes where the player has entered the current level.
+/// The level is restarted from this place
+#[derive(Debug, Clone)]
+pub struct LevelEntry {
+    pub level: LevelSelection,
+
+    /// World coordinates where the player has appeared
+    pub translation: Vec3,
+}
+
+/// Full screen node which hides the level changing
+#[derive(Component)]
+pub struct TransitionFade;
+
+/// How long (in seconds) the screen fades out and fades in
+const FADE_DURATION: f32 = 0.4;
+
+/// How long (in seconds) we are waiting for the destination entity.
+/// The map might reference a level or an entity which doesn't exist
+const LOADING_TIMEOUT: f32 = 3.0;
+
+impl Plugin for LevelTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(start_level_transition.run_in_state(ApplicationState::Game))
+            .add_system(remember_level_entry.run_in_state(ApplicationState::Game))
+            .add_system(
+                process_level_transition
+                    .run_in_state(ApplicationState::Game)
+                    .run_if_resource_exists::<LevelTransition>(),
+            )
+            .add_event::<LevelTransitionEvent>();
+    }
+}
+
+fn start_level_transition(
+    mut commands: Commands,
+    level_transition: Option<Res<LevelTransition>>,
+    mut level_transition_event: EventReader<LevelTransitionEvent>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    let event = match level_transition_event.iter().last() {
+        Some(event) => event,
+        None => return,
+    };
+
+    // Ignore all new transitions until the current one is finished
+    if level_transition.is_some() {
+        return;
+    }
+
+    commands.insert_resource(LevelTransition {
+        destination: event.clone(),
+        stage: TransitionStage::FadeOut,
+        timer: Timer::from_seconds(FADE_DURATION, false),
+    });
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                ..Default::default()
+            },
+            color: UiColor(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+            ..Default::default()
+        })
+        .insert(TransitionFade);
+
+    // The player must not move while the screen is black
+    rapier_config.physics_pipeline_active = false;
+}
+
+/// The place where the player appears (e.g. the player start
+///  or the checkpoint after death) becomes the level entry
+fn remember_level_entry(
+    mut commands: Commands,
+    level_selection: Res<LevelSelection>,
+    player_query: Query<&Transform, Added<Player>>,
+) {
+    if let Ok(player_transform) = player_query.get_single() {
+        commands.insert_resource(LevelEntry {
+            level: level_selection.clone(),
+            translation: player_transform.translation,
+        });
+    }
+}
+
+// Allow too many arguments for specific function
+#[allow(clippy::too_many_arguments)]
+fn process_level_transition(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut level_transition: ResMut<LevelTransition>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    active_checkpoint: Option<Res<ActiveCheckpoint>>,
+    mut fade_query: Query<(Entity, &mut UiColor), With<TransitionFade>>,
+    mut player_query: Query<(&mut Transform, &mut Velocity), With<Player>>,
+    destination_query: Query<(
+        &EntityInstance,
+        &GlobalTransform,
+        ChangeTrackers<EntityInstance>,
+    )>,
+) {
+    let (fade_entity, mut fade_color) = fade_query
+        .get_single_mut()
+        .expect("Transition fade must exist during the level transition");
+
+    match level_transition.stage {
+        TransitionStage::FadeOut => {
+            level_transition.timer.tick(time.delta());
+            fade_color.0.set_a(level_transition.timer.percent());
+
+            if level_transition.timer.finished() {
+                *level_selection =
+                    LevelSelection::Iid(level_transition.destination.level_iid.clone());
+
+                level_transition.timer = Timer::from_seconds(LOADING_TIMEOUT, false);
+                level_transition.stage = TransitionStage::Loading;
+            }
+        }
+        TransitionStage::Loading => {
+            level_transition.timer.tick(time.delta());
+
+            // The destination entity appears only when its level has been spawned.
+            // Skip just added entities because their `GlobalTransform`
+            //  is not calculated yet
+            let destination =
+                destination_query
+                    .iter()
+                    .find(|(entity_instance, _, entity_instance_tracker)| {
+                        entity_instance.iid == level_transition.destination.entity_iid
+                            && !entity_instance_tracker.is_added()
+                    });
+
+            let destination_translation = match destination {
+                Some((_, destination_transform, _)) => destination_transform.translation(),
+                None if level_transition.timer.finished() => {
+                    error!(
+                        "Level transition destination {:?} is not found. \
+                         The player is moved to the last checkpoint",
+                        level_transition.destination
+                    );
+
+                    // The player start is the checkpoint until any other is reached
+                    let active_checkpoint = match active_checkpoint {
+                        Some(active_checkpoint) => active_checkpoint,
+                        None => {
+                            level_transition.timer = Timer::from_seconds(FADE_DURATION, false);
+                            level_transition.stage = TransitionStage::FadeIn;
+
+                            return;
+                        }
+                    };
+
+                    *level_selection = active_checkpoint.level.clone();
+
+                    active_checkpoint.translation
+                }
+                None => return,
+            };
+
+            if let Ok((mut player_transform, mut velocity)) = player_query.get_single_mut() {
+                player_transform.translation.x = destination_translation.x;
+                player_transform.translation.y = destination_translation.y;
+                *velocity = Velocity::zero();
+
+                commands.insert_resource(LevelEntry {
+                    level: level_selection.clone(),
+                    translation: player_transform.translation,
+                });
+            }
+
+            level_transition.timer = Timer::from_seconds(FADE_DURATION, false);
+            level_transition.stage = TransitionStage::FadeIn;
+        }
+        TransitionStage::FadeIn => {
+            level_transition.timer.tick(time.delta());
+            fade_color.0.set_a(level_transition.timer.percent_left());
+
+            if level_transition.timer.finished() {
+                commands.entity(fade_entity).despawn_recursive();
+                commands.remove_resource::<LevelTransition>();
+
+                rapier_config.physics_pipeline_active = true;
+            }
+        }
+    }
+}
@@ -1,17 +1,28 @@
 #![allow(clippy::forget_non_drop)]
 
+mod actions;
 mod audio;
+mod checkpoint;
 mod combat;
 mod common;
 mod debug;
+mod door;
+mod elevator;
 mod enemy;
+mod fixed_timestep;
 mod hud;
+mod interaction;
+mod items;
 mod ldtk;
+mod level_transition;
 mod map;
 mod out_of_bounce;
 mod physics;
 mod player;
+mod projectile;
+mod replay;
 mod ron_parsers;
+mod save;
 mod tests;
 mod tutorial;
 mod ui;
@@ -19,17 +30,28 @@ mod ui;
 use bevy::{prelude::*, render::texture::ImageSettings};
 use iyes_loopless::prelude::*;
 
+use actions::ActionsPlugin;
 use audio::GameAudioPlugin;
+use checkpoint::CheckpointPlugin;
 use combat::CombatPlugin;
 use debug::DebugPlugin;
+use door::DoorPlugin;
+use elevator::ElevatorPlugin;
 use enemy::EnemyPlugin;
+use fixed_timestep::FixedTimestepPlugin;
 use hud::HudPlugin;
+use interaction::InteractionPlugin;
+use items::ItemsPlugin;
 use ldtk::GameLdtkPlugin;
+use level_transition::LevelTransitionPlugin;
 use map::MapPlugin;
 use out_of_bounce::OutOfBouncePlugin;
 use physics::PhysicsPlugin;
 use player::{PlayerAnimationState, PlayerPlugin};
+use projectile::ProjectilePlugin;
+use replay::{ReplayMode, ReplayPlugin};
 use ron_parsers::RonParsersPlugin;
+use save::SavePlugin;
 use ui::UIPlugin;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -52,20 +74,75 @@ enum ApplicationStateMenu {
 
     /// Describes that currently a player in a settings menu (turn on/off, change volume of the music, etc...)
     Settings,
+
+    /// Describes that currently a player in a controls page of settings (rebind keys of actions)
+    Controls,
+
+    /// Describes that currently a player in an inventory menu (look at the items, use them, etc...)
+    Inventory,
+
+    /// Describes that currently a player in a save slots menu (save or load the game)
+    SaveSlots,
+}
+
+/// Describes which game the player plays.
+/// Selected once on startup by `--top-down` command line flag
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum GameMode {
+    /// Side view camera with gravity, jumps and climbing
+    Platformer,
+
+    /// Top view camera (dungeon crawler) without gravity
+    TopDown,
+}
+
+impl GameMode {
+    pub fn from_args() -> Self {
+        if std::env::args().any(|arg| arg == "--top-down") {
+            Self::TopDown
+        } else {
+            Self::Platformer
+        }
+    }
+
+    /// Returns the LDtk map which is played in this mode
+    pub fn map_path(&self) -> &'static str {
+        match self {
+            Self::Platformer => "Typical_2D_platformer_wip.ldtk",
+            Self::TopDown => "top_down_map.ldtk",
+        }
+    }
 }
 
 pub struct PauseTheGameEvent;
 pub struct ResumeTheGameEvent;
 
+/// Should be fired when the player opens the inventory during the game
+pub struct OpenInventoryEvent;
+
 pub struct ExitTheGameEvent;
 
+/// Should be fired when the level has to be started from the beginning
+pub struct RestartLevelEvent;
+
 #[derive(Debug)]
 pub struct PlayerIsDeadEvent;
 
-/// Should be fire when the player get hit but not dead
-/// Accepts how many points the player receives
+/// Should be fired when any `Attackable` entity gets hit
 #[derive(Debug)]
-pub struct PlayerIsHitEvent(i32);
+pub struct DamageEvent {
+    /// The entity which hits
+    pub source: Entity,
+
+    /// The entity which gets hit
+    pub target: Entity,
+
+    /// How many health points the target loses
+    pub amount: i32,
+
+    /// Impulse which pushes the target
+    pub knockback: Vec2,
+}
 
 fn setup(mut commands: Commands) {
     commands.spawn_bundle(Camera2dBundle::default());
@@ -82,14 +159,22 @@ fn main() {
             ..Default::default()
         })
         .insert_resource(ImageSettings::default_nearest())
+        .insert_resource(GameMode::from_args())
+        .insert_resource(ReplayMode::from_args())
         .add_loopless_state(ApplicationState::Menu(ApplicationStateMenu::Main))
         .add_loopless_state(PlayerAnimationState::Idle)
         .add_event::<PauseTheGameEvent>()
         .add_event::<ResumeTheGameEvent>()
+        .add_event::<OpenInventoryEvent>()
         .add_event::<ExitTheGameEvent>()
+        .add_event::<RestartLevelEvent>()
         .add_event::<PlayerIsDeadEvent>()
-        .add_event::<PlayerIsHitEvent>()
+        .add_event::<DamageEvent>()
         .add_plugins(DefaultPlugins)
+        .add_plugin(ActionsPlugin)
+        .add_plugin(ReplayPlugin)
+        // Creates the fixed timestep stage for gameplay plugins
+        .add_plugin(FixedTimestepPlugin)
         .add_plugin(GameLdtkPlugin)
         .add_startup_system(setup)
         .add_plugin(UIPlugin)
@@ -105,6 +190,14 @@ fn main() {
         .add_plugin(OutOfBouncePlugin)
         .add_plugin(EnemyPlugin)
         .add_plugin(CombatPlugin)
+        .add_plugin(ProjectilePlugin)
+        .add_plugin(InteractionPlugin)
+        .add_plugin(ItemsPlugin)
+        .add_plugin(DoorPlugin)
+        .add_plugin(ElevatorPlugin)
+        .add_plugin(LevelTransitionPlugin)
+        .add_plugin(CheckpointPlugin)
+        .add_plugin(SavePlugin)
         .add_plugin(HudPlugin)
         .add_plugin(DebugPlugin)
         .run();
@@ -4,8 +4,9 @@ use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_inspector_egui::Inspectable;
 use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
 
-use crate::{common::Climbable, player::Player};
+use crate::{common::Climbable, level_transition::LevelTransition, player::Player};
 
 enum CollisionId {
     Dirt = 1,
@@ -250,12 +251,22 @@ fn spawn_wall_collision(
 #[derive(Component)]
 pub struct WallCollision;
 
+/// Selects the level in which the player is placed.
+/// Levels might overlap each other (e.g. floors of the top-down map)
+///  so the current level is kept while the player stays inside it
 fn update_level_selection(
     level_query: Query<(&Handle<LdtkLevel>, &Transform), Without<Player>>,
     player_query: Query<&Transform, With<Player>>,
     mut level_selection: ResMut<LevelSelection>,
     ldtk_levels: Res<Assets<LdtkLevel>>,
 ) {
+    let player_transform = match player_query.get_single() {
+        Ok(player_transform) => player_transform,
+        Err(_) => return,
+    };
+
+    let mut next_level_iid = None;
+
     for (level_handle, level_transform) in level_query.iter() {
         if let Some(ldtk_level) = ldtk_levels.get(level_handle) {
             let level_bounds = UiRect {
@@ -265,18 +276,23 @@ fn update_level_selection(
                 right: level_transform.translation.x + ldtk_level.level.px_wid as f32,
             };
 
-            for player_transform in player_query.iter() {
-                if player_transform.translation.x < level_bounds.right
-                    && player_transform.translation.x > level_bounds.left
-                    && player_transform.translation.y < level_bounds.top
-                    && player_transform.translation.y > level_bounds.bottom
-                    && !level_selection.is_match(&0, &ldtk_level.level)
-                {
-                    *level_selection = LevelSelection::Iid(ldtk_level.level.iid.clone());
+            if player_transform.translation.x < level_bounds.right
+                && player_transform.translation.x > level_bounds.left
+                && player_transform.translation.y < level_bounds.top
+                && player_transform.translation.y > level_bounds.bottom
+            {
+                if level_selection.is_match(&0, &ldtk_level.level) {
+                    return;
                 }
+
+                next_level_iid = Some(ldtk_level.level.iid.clone());
             }
         }
     }
+
+    if let Some(level_iid) = next_level_iid {
+        *level_selection = LevelSelection::Iid(level_iid);
+    }
 }
 
 /// During loading the map we should pause the physics
@@ -287,14 +303,20 @@ fn update_level_selection(
 ///  the physics pipeline when we have an event
 ///  of rendering the map and turn it on when
 ///  the map has been loaded
+///
+/// During the level transition the physics is controlled
+///  by the transition itself
 fn pause_physics_during_map_load(
     mut level_events: EventReader<LevelEvent>,
     mut rapier_config: ResMut<RapierConfiguration>,
+    level_transition: Option<Res<LevelTransition>>,
 ) {
     for event in level_events.iter() {
         match event {
             LevelEvent::SpawnTriggered(_) => rapier_config.physics_pipeline_active = false,
-            LevelEvent::Transformed(_) => rapier_config.physics_pipeline_active = true,
+            LevelEvent::Transformed(_) if level_transition.is_none() => {
+                rapier_config.physics_pipeline_active = true
+            }
             _ => (),
         }
     }
@@ -307,7 +329,9 @@ impl Plugin for MapPlugin {
         app.add_system(fit_camera_inside_current_level)
             .add_system(pause_physics_during_map_load)
             .add_system(spawn_wall_collision)
-            .add_system(update_level_selection)
+            // Doors change the level by themselves. The player may stay
+            //  inside the previous level until the transition ends
+            .add_system(update_level_selection.run_unless_resource_exists::<LevelTransition>())
             .register_ldtk_int_cell::<DirtBundle>(CollisionId::Dirt as i32)
             .register_ldtk_int_cell::<LadderBundle>(CollisionId::Ladder as i32)
             .register_ldtk_int_cell::<StoneBundle>(CollisionId::Stone as i32);
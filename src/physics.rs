@@ -1,5 +1,13 @@
-use bevy::prelude::*;
-use bevy_rapier2d::prelude::*;
+use bevy::{ecs::event::Events, prelude::*, transform::TransformSystem};
+use bevy_rapier2d::{plugin::systems, prelude::*};
+
+use crate::{
+    fixed_timestep::{
+        FixedStepProgress, FixedTimestepAppExt, FIXED_TIMESTEP, FIXED_UPDATE, PHYSICS_STEP_STAGE,
+        PHYSICS_SYNC_STAGE, PHYSICS_WRITEBACK_STAGE,
+    },
+    GameMode,
+};
 
 pub struct PhysicsPlugin;
 
@@ -10,13 +18,243 @@ impl Plugin for PhysicsPlugin {
         // This means that 50px in graphics is the same as 1 in physics
         // Read more information there:
         //  https://rapier.rs/docs/user_guides/bevy_plugin/common_mistakes#why-is-everything-moving-in-slow-motion
-        app.add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(
-            PIXELS_PER_METER,
-        ))
+        app.add_plugin(
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER)
+                .with_default_system_setup(false),
+        )
+        .insert_resource(rapier_configuration(GameMode::Platformer))
+        .add_startup_system(configure_physics_for_game_mode)
+        // Physics makes exactly one step after gameplay systems of every fixed step
+        .add_fixed_timestep_system_set(
+            FIXED_UPDATE,
+            PHYSICS_SYNC_STAGE,
+            RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsStages::SyncBackend),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            PHYSICS_STEP_STAGE,
+            systems::step_simulation::<NoUserData>,
+        )
+        .add_fixed_timestep_system_set(
+            FIXED_UPDATE,
+            PHYSICS_WRITEBACK_STAGE,
+            RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsStages::Writeback),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            PHYSICS_WRITEBACK_STAGE,
+            record_step_translation.after(systems::writeback_rigid_bodies),
+        )
+        // Collision events are kept for two frames like any other events
+        //  however many steps have been made during these frames
+        .add_system_to_stage(CoreStage::First, Events::<CollisionEvent>::update_system)
+        .add_system_to_stage(CoreStage::First, Events::<ContactForceEvent>::update_system)
+        .add_stage_before(
+            CoreStage::Last,
+            PhysicsStages::DetectDespawn,
+            SystemStage::parallel().with_system_set(
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsStages::DetectDespawn),
+            ),
+        )
+        .add_system_to_stage(CoreStage::PreUpdate, restore_step_translation)
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            interpolate_translation.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Draws the body between its positions after two last physics steps
+///  so it moves smoothly whatever the frame rate is.
+/// Gameplay and physics always see the position after the last step
+#[derive(Component, Default)]
+pub struct RenderInterpolation {
+    /// Translations after the previous and the last steps
+    steps: Option<(Vec3, Vec3)>,
+}
+
+/// Returns physics configuration which the game starts with
+pub fn rapier_configuration(game_mode: GameMode) -> RapierConfiguration {
+    let gravity = match game_mode {
         // Change gravity from -98.0 to -300.0
-        .insert_resource(RapierConfiguration {
-            gravity: Vec2::new(0.0, -300.0),
-            ..Default::default()
-        });
+        GameMode::Platformer => Vec2::new(0.0, -300.0),
+
+        // There is no "down" direction when the camera looks from the top
+        GameMode::TopDown => Vec2::ZERO,
+    };
+
+    RapierConfiguration {
+        gravity,
+        // Physics is stepped by `FIXED_UPDATE` itself
+        //  so every call advances it by exactly one step
+        timestep_mode: TimestepMode::Fixed {
+            dt: FIXED_TIMESTEP.as_secs_f32(),
+            substeps: 1,
+        },
+        ..Default::default()
+    }
+}
+
+fn configure_physics_for_game_mode(
+    game_mode: Res<GameMode>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    *rapier_config = rapier_configuration(*game_mode);
+}
+
+fn record_step_translation(mut bodies: Query<(&Transform, &mut RenderInterpolation)>) {
+    for (transform, mut interpolation) in bodies.iter_mut() {
+        let last = transform.translation;
+        let previous = interpolation.steps.map_or(last, |(_, last)| last);
+
+        interpolation.steps = Some((previous, last));
+    }
+}
+
+/// Puts bodies back to their positions after the last step
+///  before gameplay systems and physics see them
+fn restore_step_translation(mut bodies: Query<(&mut Transform, &RenderInterpolation)>) {
+    for (mut transform, interpolation) in bodies.iter_mut() {
+        if let Some((_, last)) = interpolation.steps {
+            if transform.translation != last {
+                transform.translation = last;
+            }
+        }
+    }
+}
+
+fn interpolate_translation(
+    progress: Res<FixedStepProgress>,
+    mut bodies: Query<(&mut Transform, &mut RenderInterpolation)>,
+) {
+    for (mut transform, mut interpolation) in bodies.iter_mut() {
+        match interpolation.steps {
+            Some((previous, last)) if transform.translation == last => {
+                let interpolated = previous.lerp(last, progress.0);
+
+                if transform.translation != interpolated {
+                    transform.translation = interpolated;
+                }
+            }
+            // The body has just been spawned or moved by gameplay
+            _ => {
+                interpolation.steps = Some((transform.translation, transform.translation));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod physics_tests {
+    use std::time::Duration;
+
+    use bevy::asset::AssetPlugin;
+    use bevy::hierarchy::HierarchyPlugin;
+    use bevy::prelude::*;
+    use bevy::transform::TransformPlugin;
+    use bevy_rapier2d::prelude::*;
+
+    use crate::actions::Action;
+    use crate::fixed_timestep::{FixedTimestepPlugin, ForcedFrameDuration, FIXED_TIMESTEP};
+    use crate::GameMode;
+
+    use super::{PhysicsPlugin, RenderInterpolation};
+
+    /// Prepares the headless app with physics running
+    ///  at the given frame duration and a body which moves
+    ///  by one pixel every step. Returns the body entity
+    fn prepare_app(frame_duration: Duration) -> (App, Entity) {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            // Rapier looks for meshes and scenes of async colliders
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<Scene>()
+            .insert_resource(GameMode::TopDown)
+            .init_resource::<Input<Action>>()
+            .add_plugin(FixedTimestepPlugin)
+            .add_plugin(PhysicsPlugin);
+
+        let body_id = app
+            .world
+            .spawn()
+            .insert(RigidBody::Dynamic)
+            .insert(Collider::ball(5.0))
+            .insert(Velocity::linear(Vec2::new(
+                1.0 / FIXED_TIMESTEP.as_secs_f32(),
+                0.0,
+            )))
+            .insert(RenderInterpolation::default())
+            .insert_bundle(TransformBundle::default())
+            .id();
+
+        // The body is spawned before the first step
+        app.update();
+        app.insert_resource(ForcedFrameDuration(frame_duration));
+
+        (app, body_id)
+    }
+
+    fn translation_x(app: &App, body_id: Entity) -> f32 {
+        app.world
+            .get::<Transform>(body_id)
+            .expect("Body must have a transform")
+            .translation
+            .x
+    }
+
+    #[test]
+    fn should_draw_the_body_between_steps() {
+        let (mut app, body_id) = prepare_app(FIXED_TIMESTEP / 2);
+
+        // The body is drawn one step behind physics
+        //  so it starts moving on the second step
+        for _ in 0..2 {
+            app.update();
+        }
+
+        let mut positions = vec![];
+
+        for _ in 0..6 {
+            app.update();
+            positions.push(translation_x(&app, body_id));
+        }
+
+        // Every frame is a half of the step
+        //  so the body moves by a half of the pixel
+        for (previous, next) in positions.iter().zip(positions.iter().skip(1)) {
+            assert!(
+                (next - previous - 0.5).abs() < 0.001,
+                "{:?} must grow evenly",
+                positions
+            );
+        }
+    }
+
+    #[test]
+    fn should_show_the_last_step_to_gameplay_systems() {
+        let (mut app, body_id) = prepare_app(FIXED_TIMESTEP / 2);
+
+        #[derive(Default)]
+        struct SeenTranslationX(f32);
+
+        app.init_resource::<SeenTranslationX>().add_system(
+            |bodies: Query<&Transform, With<RenderInterpolation>>,
+             mut seen: ResMut<SeenTranslationX>| {
+                seen.0 = bodies.single().translation.x;
+            },
+        );
+
+        // Three steps and a half of the next one
+        for _ in 0..7 {
+            app.update();
+        }
+
+        // The body is drawn in the middle of the second and the third steps
+        assert!((translation_x(&app, body_id) - 2.5).abs() < 0.001);
+        assert!((app.world.resource::<SeenTranslationX>().0 - 3.0).abs() < 0.001);
     }
 }
@@ -3,8 +3,8 @@ use bevy::prelude::*;
 mod player_animations;
 mod player_physics;
 
-pub use player_animations::{PlayerAnimationState, PlayerProcessAnimation};
-pub use player_physics::{GroundDetection, JumpState, Player, SideDetector, SideSensor};
+pub use player_animations::{DeadPlayer, PlayerAnimationState, PlayerProcessAnimation};
+pub use player_physics::{GroundSensor, JumpState, Player, SideDetector};
 
 pub struct PlayerPlugin;
 
@@ -1,12 +1,15 @@
 use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
 use bevy_inspector_egui::Inspectable;
 use bevy_rapier2d::prelude::*;
 use iyes_loopless::prelude::*;
 
-use crate::common::{
-    Attacks, Climber, FastAnimation, LangeAnimation, MediumAnimation, OnMove, XFastAnimation,
+use crate::common::{Attacks, Climber, FastAnimation, LangeAnimation, MediumAnimation, OnMove};
+use crate::{
+    items::{Equipment, Inventory},
+    ron_parsers::GameTextures,
+    ApplicationState, DamageEvent, PlayerIsDeadEvent,
 };
-use crate::{ron_parsers::GameTextures, ApplicationState, PlayerIsDeadEvent, PlayerIsHitEvent};
 
 use super::{JumpState, Player, SideDetector};
 pub struct PlayerAnimationPlugin;
@@ -26,6 +29,16 @@ impl Default for PlayerProcessAnimation {
 #[derive(Component)]
 struct PlayerDeath;
 
+/// Keeps everything which is needed to re-create the removed player
+pub struct DeadPlayer {
+    pub worldly: Worldly,
+    pub entity_instance: EntityInstance,
+
+    /// Contains equipped items as well because
+    ///  the new player appears without equipment
+    pub inventory: Inventory,
+}
+
 /// Describes animation state of the player
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Inspectable)]
 pub enum PlayerAnimationState {
@@ -98,6 +111,10 @@ impl Plugin for PlayerAnimationPlugin {
                         .run_in_state(PlayerAnimationState::Death(PlayerProcessAnimation::Start)),
                 )
                 .into(),
+        )
+        .add_exit_system(
+            PlayerAnimationState::Death(PlayerProcessAnimation::End),
+            remove_player_death,
         );
     }
 }
@@ -115,25 +132,42 @@ fn setup(mut commands: Commands, player_query: Query<Entity, Added<Player>>) {
             })
             .insert(FastAnimation {
                 timer: Timer::from_seconds(0.05, true),
-            })
-            .insert(XFastAnimation {
-                timer: Timer::from_seconds(0.04, true),
             });
     }
 }
 
 /// Triggers when `animation_state` has changed and update user texture
+#[allow(clippy::type_complexity)]
 fn player_animation_textures_processor(
     mut commands: Commands,
     materials: Res<GameTextures>,
     animation_state: Res<CurrentState<PlayerAnimationState>>,
     mut player_query: Query<
-        (Entity, &Transform, &mut TextureAtlasSprite, &mut Attacks),
+        (
+            Entity,
+            &Transform,
+            &mut TextureAtlasSprite,
+            &mut Attacks,
+            &Worldly,
+            &EntityInstance,
+            &Inventory,
+            &Equipment,
+        ),
         With<Player>,
     >,
 ) {
     if animation_state.is_changed() {
-        if let Ok((entity, transform, mut sprite, mut attacks)) = player_query.get_single_mut() {
+        if let Ok((
+            entity,
+            transform,
+            mut sprite,
+            mut attacks,
+            worldly,
+            entity_instance,
+            inventory,
+            equipment,
+        )) = player_query.get_single_mut()
+        {
             sprite.index = 0;
 
             match animation_state.0 {
@@ -154,16 +188,15 @@ fn player_animation_textures_processor(
                 }
                 PlayerAnimationState::Hit(hit_animation) => match hit_animation {
                     PlayerProcessAnimation::Start => {
+                        // Interrupt the swing when the player receives a hit
+                        //  to avoid hurting enemies during the hit animation
+                        attacks.cancel();
+
                         commands
                             .entity(entity)
                             .insert(materials.player.hurt.texture.clone());
                     }
                     PlayerProcessAnimation::End => {
-                        // Stop user attack state when user received hit
-                        //  to avoid the problem when the user will attack
-                        //  after hit animation will be stopped
-                        attacks.0 = false;
-
                         commands.insert_resource(NextState(PlayerAnimationState::Idle));
                     }
                 },
@@ -183,9 +216,8 @@ fn player_animation_textures_processor(
                             .entity(entity)
                             .insert(materials.player.attack.texture.clone());
                     }
-                    PlayerProcessAnimation::End => {
-                        attacks.0 = false;
-                    }
+                    // The swing is finished by combat systems
+                    PlayerProcessAnimation::End => (),
                 },
                 PlayerAnimationState::WallSlide => {
                     commands
@@ -210,6 +242,21 @@ fn player_animation_textures_processor(
                             })
                             .insert(PlayerDeath);
 
+                        let mut inventory = inventory.clone();
+                        for item in [equipment.weapon, equipment.boots, equipment.armor]
+                            .into_iter()
+                            .flatten()
+                        {
+                            inventory.add(item);
+                        }
+
+                        // Remember the player to be able to respawn it
+                        commands.insert_resource(DeadPlayer {
+                            worldly: worldly.clone(),
+                            entity_instance: entity_instance.clone(),
+                            inventory,
+                        });
+
                         // Remove the player from the scene
                         commands.entity(entity).despawn_recursive();
                     }
@@ -222,18 +269,42 @@ fn player_animation_textures_processor(
     }
 }
 
+/// Removes hidden death animation when the player is respawned
+fn remove_player_death(mut commands: Commands, death_query: Query<Entity, With<PlayerDeath>>) {
+    for death_entity in death_query.iter() {
+        commands.entity(death_entity).despawn_recursive();
+    }
+}
+
 /// Handle all physical changes and set correct player animation state
 #[allow(clippy::type_complexity)]
 fn player_animation_processor(
     player_animation_state: Res<CurrentState<PlayerAnimationState>>,
     mut commands: Commands,
-    mut player_query: Query<(&OnMove, &Climber, &JumpState, &SideDetector, &Attacks), With<Player>>,
-    mut player_hit_event: EventReader<PlayerIsHitEvent>,
+    mut player_query: Query<
+        (
+            Entity,
+            &OnMove,
+            &Climber,
+            &JumpState,
+            &SideDetector,
+            &Attacks,
+        ),
+        With<Player>,
+    >,
+    mut damage_event: EventReader<DamageEvent>,
     mut player_death_event: EventReader<PlayerIsDeadEvent>,
 ) {
-    if let Ok((on_move, climber, jump_state, side_detector, attacks)) =
+    if let Ok((player_entity, on_move, climber, jump_state, side_detector, attacks)) =
         player_query.get_single_mut()
     {
+        // Read all events to not see them again on the next frame
+        let is_hit = damage_event
+            .iter()
+            .filter(|damage| damage.target == player_entity)
+            .count()
+            > 0;
+
         if player_death_event.iter().next().is_some() {
             commands.insert_resource(NextState(PlayerAnimationState::Death(
                 PlayerProcessAnimation::Start,
@@ -242,7 +313,7 @@ fn player_animation_processor(
             return;
         }
 
-        if player_hit_event.iter().next().is_some() {
+        if is_hit {
             commands.insert_resource(NextState(PlayerAnimationState::Hit(
                 PlayerProcessAnimation::Start,
             )));
@@ -258,7 +329,7 @@ fn player_animation_processor(
             return;
         }
 
-        if attacks.0 {
+        if attacks.is_attacking() {
             if player_animation_state.0
                 != PlayerAnimationState::Attack(PlayerProcessAnimation::Start)
             {
@@ -439,22 +510,19 @@ fn player_double_jump_animation(
     }
 }
 
+/// Shows the frame of the attack sprite sheet which matches the swing
 fn player_attack_animation(
     mut commands: Commands,
-    time: Res<Time>,
-    materials: Res<GameTextures>,
-    mut query: Query<(&mut TextureAtlasSprite, &mut XFastAnimation), With<Player>>,
+    mut query: Query<(&mut TextureAtlasSprite, &Attacks), With<Player>>,
 ) {
-    let player_materials = &materials.player;
-
-    for (mut sprite, mut animation) in query.iter_mut() {
-        animation.timer.tick(time.delta());
-        if animation.timer.finished() {
-            sprite.index += 1;
-
-            if sprite.index >= player_materials.attack.items {
-                sprite.index = 0;
-
+    for (mut sprite, attacks) in query.iter_mut() {
+        match attacks.frame() {
+            Some(frame) => {
+                if sprite.index != frame {
+                    sprite.index = frame;
+                }
+            }
+            None => {
                 commands.insert_resource(NextState(PlayerAnimationState::Attack(
                     PlayerProcessAnimation::End,
                 )));
@@ -6,7 +6,15 @@ use bevy_inspector_egui::Inspectable;
 use bevy_rapier2d::prelude::*;
 use iyes_loopless::prelude::*;
 
-use crate::{map::WallCollision, ron_parsers::GameTextures, ApplicationState, PlayerIsDeadEvent};
+use crate::{
+    actions::{action_strength, Action},
+    fixed_timestep::{FixedTimestepAppExt, FIXED_UPDATE, GAMEPLAY_STAGE},
+    items::{Equipment, Inventory},
+    map::WallCollision,
+    physics::RenderInterpolation,
+    ron_parsers::GameTextures,
+    ApplicationState, GameMode, PlayerIsDeadEvent,
+};
 
 use crate::common::{
     Attackable, Attacks, Climbable, Climber, Health, MovementDirection, OnMove, Speed,
@@ -34,8 +42,10 @@ pub struct GroundDetection {
 pub struct SideDetector {
     pub on_side: bool,
 }
+/// Sensor under the entity which detects the ground
+///  and enemies which are stomped by the player
 #[derive(Component)]
-struct GroundSensor {
+pub struct GroundSensor {
     pub detection_entity: Entity,
     pub intersecting_entities: HashSet<Entity>,
 }
@@ -56,6 +66,9 @@ struct PlayerBundle {
 
     #[from_entity_instance]
     entity_instance: EntityInstance,
+
+    #[from_entity_instance]
+    pub inventory: Inventory,
 }
 
 /// Doesn't matter which values we will set
@@ -73,28 +86,53 @@ pub struct PlayerPhysicsPlugin;
 
 impl Plugin for PlayerPhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(
+        // Movement and jumps run on the fixed timestep to have
+        //  the same speed and jump height with any frame rate
+        app.add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
             sync_jumps_made
                 .run_in_state(ApplicationState::Game)
+                .run_if_resource_equals(GameMode::Platformer)
                 .before("jump"),
         )
-        .add_system(
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
             player_jump
                 .run_in_state(ApplicationState::Game)
+                .run_if_resource_equals(GameMode::Platformer)
                 .label("jump")
                 .after("movement"),
         )
-        .add_system(
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
             player_movement
                 .run_in_state(ApplicationState::Game)
+                .run_if_resource_equals(GameMode::Platformer)
                 .label("movement"),
         )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            player_top_down_movement
+                .run_in_state(ApplicationState::Game)
+                .run_if_resource_equals(GameMode::TopDown)
+                .label("movement"),
+        )
+        .add_fixed_timestep_system(
+            FIXED_UPDATE,
+            GAMEPLAY_STAGE,
+            ignore_gravity_during_climbing.run_in_state(ApplicationState::Game),
+        )
+        // Collision events are read every frame because they are
+        //  cleared after two frames and steps might be rarer
         .add_system_set(
             ConditionSet::new()
                 .run_in_state(ApplicationState::Game)
                 .with_system(spawn_player)
                 .with_system(detect_climb)
-                .with_system(ignore_gravity_during_climbing)
                 .with_system(spawn_ground_sensor)
                 .with_system(spawn_side_sensor)
                 .with_system(ground_detection)
@@ -128,9 +166,14 @@ fn spawn_player(
             .insert(ExternalImpulse::default())
             .insert(Friction::new(0.0))
             .insert(LockedAxes::ROTATION_LOCKED)
+            // Draw the player between physics steps
+            .insert(RenderInterpolation::default())
             .insert(GravityScale(GRAVITY_SCALE))
             .insert(ColliderMassProperties::Density(1.0))
-            .insert(Attacks(false))
+            .insert(Attacks {
+                frames: materials.player.attack_swing,
+                ..default()
+            })
             .insert(Attackable)
             .insert_bundle(SpriteSheetBundle {
                 texture_atlas: sprite_asset_info.texture.clone(),
@@ -164,7 +207,8 @@ fn spawn_player(
             .insert(GroundDetection { on_ground: false })
             .insert(SideDetector { on_side: false })
             .insert(Health { current: 5, max: 5 })
-            .insert(Speed(110.0));
+            .insert(Speed(110.0))
+            .insert(Equipment::default());
     }
 }
 
@@ -202,10 +246,12 @@ fn sync_jumps_made(
 ///  moving to left / right (change `x` velocity)
 ///  climbing (change `y` velocity)
 fn player_movement(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
+    axes: Res<Axis<Action>>,
     mut query: Query<
         (
             &Speed,
+            &Equipment,
             &mut OnMove,
             &mut Climber,
             &mut MovementDirection,
@@ -215,19 +261,30 @@ fn player_movement(
         With<Player>,
     >,
 ) {
-    if let Ok((speed, mut on_move, mut climber, mut direction, mut sprite, mut velocity)) =
-        query.get_single_mut()
+    if let Ok((
+        speed,
+        equipment,
+        mut on_move,
+        mut climber,
+        mut direction,
+        mut sprite,
+        mut velocity,
+    )) = query.get_single_mut()
     {
+        // Equipment (e.g. boots) makes the player faster
+        let speed = speed.0 + equipment.modifiers.speed;
+
         /* Moving logic */
-        let direction_x = if keyboard.pressed(KeyCode::Left) {
-            -1.0
-        } else if keyboard.pressed(KeyCode::Right) {
-            1.0
+        // Partially tilted stick moves the player slower
+        let direction_x = if actions.pressed(Action::MoveLeft) {
+            -action_strength(&actions, &axes, Action::MoveLeft)
+        } else if actions.pressed(Action::MoveRight) {
+            action_strength(&actions, &axes, Action::MoveRight)
         } else {
             0.0
         };
 
-        let move_delta_x = direction_x * speed.0;
+        let move_delta_x = direction_x * speed;
 
         // Update player velocity
         velocity.linvel.x = move_delta_x;
@@ -259,20 +316,71 @@ fn player_movement(
             if climber.climbing {
                 climber.climbing = false;
             }
-        } else if keyboard.just_pressed(KeyCode::Up) || keyboard.just_pressed(KeyCode::Down) {
+        } else if actions.just_pressed(Action::MoveUp) || actions.just_pressed(Action::MoveDown) {
             climber.climbing = true;
         }
 
         if climber.climbing {
-            let direction_y = if keyboard.pressed(KeyCode::Up) {
-                1.0
-            } else if keyboard.pressed(KeyCode::Down) {
-                -1.0
+            let direction_y = if actions.pressed(Action::MoveUp) {
+                action_strength(&actions, &axes, Action::MoveUp)
+            } else if actions.pressed(Action::MoveDown) {
+                -action_strength(&actions, &axes, Action::MoveDown)
             } else {
                 0.0
             };
 
-            velocity.linvel.y = direction_y * speed.0;
+            velocity.linvel.y = direction_y * speed;
+        }
+    }
+}
+
+/// Movement for the top-down mode. The player moves
+///  in 8 directions and there is no gravity, climbing or jumps
+fn player_top_down_movement(
+    actions: Res<Input<Action>>,
+    axes: Res<Axis<Action>>,
+    mut query: Query<
+        (
+            &Speed,
+            &Equipment,
+            &mut OnMove,
+            &mut MovementDirection,
+            &mut TextureAtlasSprite,
+            &mut Velocity,
+        ),
+        With<Player>,
+    >,
+) {
+    if let Ok((speed, equipment, mut on_move, mut direction, mut sprite, mut velocity)) =
+        query.get_single_mut()
+    {
+        let speed = speed.0 + equipment.modifiers.speed;
+
+        let move_direction = Vec2::new(
+            action_strength(&actions, &axes, Action::MoveRight)
+                - action_strength(&actions, &axes, Action::MoveLeft),
+            action_strength(&actions, &axes, Action::MoveUp)
+                - action_strength(&actions, &axes, Action::MoveDown),
+        );
+
+        // Diagonal movement must not be faster than the straight one.
+        // Partially tilted stick moves the player slower
+        velocity.linvel = move_direction.clamp_length_max(1.0) * speed;
+
+        let is_moving = move_direction != Vec2::ZERO;
+
+        // Do not touch `OnMove` component to avoid
+        //  `Changed<OnMove>` signal on every frame
+        if on_move.0 != is_moving {
+            on_move.0 = is_moving;
+        }
+
+        if move_direction.x > 0.0 {
+            *direction = MovementDirection::Right;
+            sprite.flip_x = false;
+        } else if move_direction.x < 0.0 {
+            *direction = MovementDirection::Left;
+            sprite.flip_x = true;
         }
     }
 }
@@ -281,12 +389,13 @@ fn player_movement(
 /// When the player on the ground the player jumps
 ///  only by `y` axis. But when on side by `x` and `y` axis
 fn player_jump(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
     mut player_query: Query<
         (
             &mut ExternalImpulse,
             &mut Climber,
             &Speed,
+            &Equipment,
             &mut JumpState,
             &SideDetector,
             &MovementDirection,
@@ -298,6 +407,7 @@ fn player_jump(
         mut external_impulse,
         mut climber,
         speed,
+        equipment,
         mut jump_state,
         side_detector,
         direction,
@@ -308,17 +418,17 @@ fn player_jump(
             return;
         }
 
-        if keyboard.just_pressed(KeyCode::Space) && side_detector.on_side {
+        if actions.just_pressed(Action::Jump) && side_detector.on_side {
             let x_impulse = match *direction {
                 MovementDirection::Right => -speed.0,
                 MovementDirection::Left => speed.0,
             };
 
             external_impulse.impulse.x = x_impulse * 2.0;
-            external_impulse.impulse.y = 60.0;
+            external_impulse.impulse.y = 60.0 + equipment.modifiers.jump;
             jump_state.jumps_made += 1;
-        } else if keyboard.just_pressed(KeyCode::Space) {
-            let impulse = 55.0;
+        } else if actions.just_pressed(Action::Jump) {
+            let impulse = 55.0 + equipment.modifiers.jump;
 
             external_impulse.impulse = Vec2::new(0.0, impulse);
             climber.climbing = false;
@@ -336,30 +446,31 @@ fn spawn_ground_sensor(
     detect_ground_query: Query<(Entity, &Collider), Added<GroundDetection>>,
 ) {
     for (entity, collider) in detect_ground_query.iter() {
-        if let Some(cuboid) = collider.as_cuboid() {
-            let half_extents = &cuboid.half_extents();
-
-            let detector_shape = Collider::cuboid(half_extents.x - 2.0, 2.0);
-            let sensor_translation = Vec3::new(0.0, -half_extents.y, 0.0);
-
-            commands.entity(entity).with_children(|parent| {
-                parent
-                    .spawn()
-                    .insert(Sensor)
-                    .insert(detector_shape)
-                    // .insert(detector_shape)
-                    .insert(Transform::from_translation(sensor_translation))
-                    // We should make the weight of this rigid body as 0 because
-                    //  otherwise it will affect the user but we want to make it
-                    //  just as trigger for ground detection reaction
-                    .insert(ActiveEvents::COLLISION_EVENTS)
-                    .insert(ColliderMassProperties::Density(0.0))
-                    .insert(GroundSensor {
-                        detection_entity: entity,
-                        intersecting_entities: HashSet::new(),
-                    });
-            });
-        }
+        // Bounding box is used to place the sensor under any collider shape
+        let aabb = collider.raw.compute_local_aabb();
+        let half_extents = Vec2::new(aabb.half_extents().x, aabb.half_extents().y);
+        let bottom = aabb.center().y - half_extents.y;
+
+        let detector_shape = Collider::cuboid(half_extents.x - 2.0, 2.0);
+        let sensor_translation = Vec3::new(0.0, bottom, 0.0);
+
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn()
+                .insert(Sensor)
+                .insert(detector_shape)
+                // .insert(detector_shape)
+                .insert(Transform::from_translation(sensor_translation))
+                // We should make the weight of this rigid body as 0 because
+                //  otherwise it will affect the user but we want to make it
+                //  just as trigger for ground detection reaction
+                .insert(ActiveEvents::COLLISION_EVENTS)
+                .insert(ColliderMassProperties::Density(0.0))
+                .insert(GroundSensor {
+                    detection_entity: entity,
+                    intersecting_entities: HashSet::new(),
+                });
+        });
     }
 }
 
@@ -584,18 +695,142 @@ fn dead(
 
 #[cfg(test)]
 mod player_tests {
+    use crate::actions::{Action, ActionsPlugin};
     use crate::common::{Climber, Health, MovementDirection, Speed};
+    use crate::fixed_timestep::{FixedTimestepPlugin, ForcedFrameDuration};
+    use crate::physics::PhysicsPlugin;
     use crate::player::player_physics::{player_jump, spawn_player, PlayerBundle};
-    use crate::player::{GroundDetection, JumpState};
+    use crate::player::JumpState;
     use crate::player::{Player, SideDetector};
+    use crate::replay::{Replay, ReplayFrame, ReplayMode, ReplayPlayback, ReplayPlugin};
+    use crate::tests::settings::prepare_settings;
     use crate::tests::sprites_textures::prepare_sprites;
-    use crate::PlayerIsDeadEvent;
+    use crate::{ApplicationState, GameMode, PlayerIsDeadEvent};
+    use bevy::asset::AssetPlugin;
     use bevy::ecs::event::Events;
+    use bevy::hierarchy::HierarchyPlugin;
     use bevy::prelude::*;
+    use bevy::transform::TransformPlugin;
     use bevy_ecs_ldtk::prelude::*;
-    use bevy_rapier2d::prelude::{ExternalImpulse, GravityScale, Velocity};
+    use bevy_rapier2d::prelude::*;
+    use iyes_loopless::prelude::*;
+    use std::time::Duration;
 
-    use super::{dead, ignore_gravity_during_climbing, player_movement};
+    use super::{
+        dead, ignore_gravity_during_climbing, player_movement, GroundDetection, PlayerPhysicsPlugin,
+    };
+
+    /// Prepares the headless app with physics, the ground
+    ///  and the player on it. Returns the player entity
+    fn prepare_physics_app() -> (App, Entity) {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            // Rapier looks for meshes and scenes of async colliders
+            .add_plugin(AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<Scene>()
+            .insert_resource(GameMode::Platformer)
+            .insert_resource(prepare_sprites())
+            .insert_resource(prepare_settings())
+            .insert_resource(Input::<KeyCode>::default())
+            .add_event::<GamepadEvent>()
+            .add_event::<PlayerIsDeadEvent>()
+            .add_loopless_state(ApplicationState::Game)
+            .add_plugin(ActionsPlugin)
+            .add_plugin(FixedTimestepPlugin)
+            .add_plugin(PhysicsPlugin)
+            .add_plugin(PlayerPhysicsPlugin);
+
+        // The ground under the player
+        app.world
+            .spawn()
+            .insert(RigidBody::Fixed)
+            .insert(Collider::cuboid(500.0, 10.0))
+            .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                0.0, -20.0, 0.0,
+            )));
+
+        let player_id = app
+            .world
+            .spawn()
+            .insert(Player)
+            .insert(Transform::from_xyz(0.0, 0.0, 1.0))
+            .id();
+
+        (app, player_id)
+    }
+
+    /// Plays the replay in the headless app with physics
+    ///  and returns the final state of the player
+    fn play_replay(replay: Replay) -> (Transform, Health) {
+        let (mut app, player_id) = prepare_physics_app();
+
+        app.insert_resource(ReplayMode::Off)
+            .insert_resource(ReplayPlayback::new(replay))
+            .add_plugin(ReplayPlugin);
+
+        while !app.world.resource::<ReplayPlayback>().is_finished() {
+            app.update();
+        }
+
+        let transform = app
+            .world
+            .get::<Transform>(player_id)
+            .cloned()
+            .expect("Player must have a transform");
+        let health = app
+            .world
+            .get::<Health>(player_id)
+            .cloned()
+            .expect("Player must have a health");
+
+        (transform, health)
+    }
+
+    /// Runs 1.5 seconds of the game where the player moves
+    ///  to the right and jumps. Returns the final player transform
+    fn play_at_frame_rate(frames_per_second: u32) -> Transform {
+        let (mut app, player_id) = prepare_physics_app();
+
+        let frame_duration = Duration::from_secs(1) / frames_per_second;
+
+        // The player is spawned before the first step at any frame rate
+        app.update();
+        app.insert_resource(ForcedFrameDuration(frame_duration));
+
+        let mut elapsed = Duration::ZERO;
+
+        while elapsed < Duration::from_millis(1500) {
+            let mut keyboard = app.world.resource_mut::<Input<KeyCode>>();
+
+            if elapsed >= Duration::from_millis(250) && elapsed < Duration::from_millis(750) {
+                keyboard.press(KeyCode::Right);
+            } else {
+                keyboard.release(KeyCode::Right);
+            }
+
+            // The jump key is pressed only during one frame
+            if elapsed >= Duration::from_millis(500)
+                && elapsed < Duration::from_millis(500) + frame_duration
+            {
+                keyboard.press(KeyCode::Space);
+            } else {
+                keyboard.release(KeyCode::Space);
+            }
+
+            app.update();
+
+            elapsed += frame_duration;
+        }
+
+        app.world
+            .get::<Transform>(player_id)
+            .cloned()
+            .expect("Player must have a transform")
+    }
 
     #[test]
     fn should_spawn_a_player_with_speed() {
@@ -645,6 +880,7 @@ mod player_tests {
         let mut app = App::new();
 
         app.insert_resource(prepare_sprites())
+            .init_resource::<Axis<Action>>()
             .add_system(spawn_player)
             .add_system(player_movement)
             .register_ldtk_entity::<PlayerBundle>("Player");
@@ -656,7 +892,7 @@ mod player_tests {
             .insert(Transform::from_xyz(0.0, 0.0, 1.0))
             .id();
 
-        let input = Input::<KeyCode>::default();
+        let input = Input::<Action>::default();
         app.insert_resource(input);
 
         // We should call first update to spawn an entity
@@ -665,8 +901,8 @@ mod player_tests {
         // update and let it be
         app.update();
 
-        let mut input = Input::<KeyCode>::default();
-        input.press(KeyCode::Right);
+        let mut input = Input::<Action>::default();
+        input.press(Action::MoveRight);
         app.insert_resource(input);
 
         app.update();
@@ -683,11 +919,56 @@ mod player_tests {
         );
     }
 
+    #[test]
+    fn should_move_the_player_slower_by_tilted_gamepad_stick() {
+        let mut app = App::new();
+
+        app.insert_resource(prepare_sprites())
+            .insert_resource(prepare_settings())
+            .insert_resource(Input::<KeyCode>::default())
+            .add_event::<GamepadEvent>()
+            .add_plugin(ActionsPlugin)
+            .add_system(spawn_player)
+            .add_system(player_movement)
+            .register_ldtk_entity::<PlayerBundle>("Player");
+
+        let player_id = app
+            .world
+            .spawn()
+            .insert(Player)
+            .insert(Transform::from_xyz(0.0, 0.0, 1.0))
+            .id();
+
+        app.update();
+
+        app.world
+            .resource_mut::<Events<GamepadEvent>>()
+            .send(GamepadEvent::new(
+                Gamepad::new(0),
+                GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, 0.6),
+            ));
+
+        app.update();
+
+        let player_velocity = app
+            .world
+            .get::<Velocity>(player_id)
+            .expect("Player must have a velocity");
+        let player_speed = app
+            .world
+            .get::<Speed>(player_id)
+            .expect("Player must have a speed");
+
+        assert!(player_velocity.linvel.x > 0.0);
+        assert!(player_velocity.linvel.x < player_speed.0);
+    }
+
     #[test]
     fn should_change_movement_direction_on_move() {
         let mut app = App::new();
 
         app.insert_resource(prepare_sprites())
+            .init_resource::<Axis<Action>>()
             .add_system(spawn_player)
             .add_system(player_movement)
             .register_ldtk_entity::<PlayerBundle>("Player");
@@ -699,7 +980,7 @@ mod player_tests {
             .insert(Transform::from_xyz(0.0, 0.0, 1.0))
             .id();
 
-        let input = Input::<KeyCode>::default();
+        let input = Input::<Action>::default();
         app.insert_resource(input);
 
         // We should call first update to spawn an entity
@@ -708,8 +989,8 @@ mod player_tests {
         // update and let it be
         app.update();
 
-        let mut input = Input::<KeyCode>::default();
-        input.press(KeyCode::Right);
+        let mut input = Input::<Action>::default();
+        input.press(Action::MoveRight);
         app.insert_resource(input);
 
         app.update();
@@ -719,8 +1000,8 @@ mod player_tests {
             Some(MovementDirection::Right)
         );
 
-        let mut input = Input::<KeyCode>::default();
-        input.press(KeyCode::Left);
+        let mut input = Input::<Action>::default();
+        input.press(Action::MoveLeft);
         app.insert_resource(input);
 
         app.update();
@@ -730,8 +1011,8 @@ mod player_tests {
             Some(MovementDirection::Left)
         );
 
-        let mut input = Input::<KeyCode>::default();
-        input.press(KeyCode::Down);
+        let mut input = Input::<Action>::default();
+        input.press(Action::MoveDown);
         app.insert_resource(input);
 
         app.update();
@@ -761,7 +1042,7 @@ mod player_tests {
             .insert(Transform::from_xyz(0.0, 0.0, 1.0))
             .id();
 
-        let input = Input::<KeyCode>::default();
+        let input = Input::<Action>::default();
         app.insert_resource(input);
 
         // We should call first update to spawn an entity
@@ -770,8 +1051,8 @@ mod player_tests {
         // update and let it be
         app.update();
 
-        let mut input = Input::<KeyCode>::default();
-        input.press(KeyCode::Space);
+        let mut input = Input::<Action>::default();
+        input.press(Action::Jump);
         app.insert_resource(input);
 
         let mut ground_detection = app
@@ -812,7 +1093,7 @@ mod player_tests {
             .insert(Transform::from_xyz(0.0, 0.0, 1.0))
             .id();
 
-        let input = Input::<KeyCode>::default();
+        let input = Input::<Action>::default();
         app.insert_resource(input);
 
         // We should call first update to spawn an entity
@@ -821,8 +1102,8 @@ mod player_tests {
         // update and let it be
         app.update();
 
-        let mut input = Input::<KeyCode>::default();
-        input.press(KeyCode::Space);
+        let mut input = Input::<Action>::default();
+        input.press(Action::Jump);
         app.insert_resource(input);
 
         let mut jump_state = app
@@ -867,7 +1148,7 @@ mod player_tests {
             .insert(Transform::from_xyz(0.0, 0.0, 1.0))
             .id();
 
-        let input = Input::<KeyCode>::default();
+        let input = Input::<Action>::default();
         app.insert_resource(input);
 
         // We should call first update to spawn an entity
@@ -876,8 +1157,8 @@ mod player_tests {
         // update and let it be
         app.update();
 
-        let mut input = Input::<KeyCode>::default();
-        input.press(KeyCode::Space);
+        let mut input = Input::<Action>::default();
+        input.press(Action::Jump);
         app.insert_resource(input);
 
         let mut side_detector = app
@@ -985,4 +1266,42 @@ mod player_tests {
         // Gravity should be 0.0 when player is climbing
         assert_eq!(player_gravity.0, 0.0);
     }
+
+    #[test]
+    fn should_repeat_the_same_game_by_the_replay() {
+        let idle = ReplayFrame::default();
+        let move_right = ReplayFrame {
+            actions: vec![(Action::MoveRight, 1.0)],
+        };
+        let jump = ReplayFrame {
+            actions: vec![(Action::MoveRight, 1.0), (Action::Jump, 1.0)],
+        };
+
+        let mut frames = vec![idle.clone(); 30];
+        frames.extend(vec![move_right.clone(); 30]);
+        frames.push(jump);
+        frames.extend(vec![move_right; 30]);
+        frames.extend(vec![idle; 30]);
+
+        let replay = Replay { frames };
+
+        let (first_transform, first_health) = play_replay(replay.clone());
+        let (second_transform, second_health) = play_replay(replay);
+
+        assert!(first_transform.translation.x > 0.0);
+        assert_eq!(first_transform.translation, second_transform.translation);
+        assert_eq!(first_health.current, first_health.max);
+        assert_eq!(first_health.current, second_health.current);
+    }
+
+    #[test]
+    fn should_move_and_jump_equally_at_different_frame_rates() {
+        let slow = play_at_frame_rate(32);
+        let normal = play_at_frame_rate(64);
+        let fast = play_at_frame_rate(128);
+
+        assert!(normal.translation.x > 0.0);
+        assert_eq!(slow.translation, normal.translation);
+        assert_eq!(fast.translation, normal.translation);
+    }
 }
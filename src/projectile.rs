@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    combat::find_pair,
+    common::Attackable,
+    enemy::Enemy,
+    fixed_timestep::{FixedTimestepAppExt, FIXED_TIMESTEP, FIXED_UPDATE, GAMEPLAY_STAGE},
+    map::{Wall, WallCollision},
+    physics::RenderInterpolation,
+    ApplicationState, DamageEvent,
+};
+
+pub struct ProjectilePlugin;
+
+/// Projectiles don't have sprite sheets yet
+///  that's why they are drawn as colored squares
+const PROJECTILE_COLOR: Color = Color::rgb(0.6, 0.9, 0.3);
+
+/// Flies straight until it hits a wall or an `Attackable` entity
+#[derive(Component, Debug, Clone)]
+pub struct Projectile {
+    /// The entity which has shot. It's never hit by its own projectile
+    pub shooter: Entity,
+
+    /// How many health points the target loses
+    pub damage: i32,
+
+    /// Impulse which pushes the target when the projectile flies to the right.
+    /// It's mirrored for projectiles which fly to the left
+    pub knockback: Vec2,
+
+    /// Time until the missed projectile disappears
+    pub lifetime: Duration,
+}
+
+/// Should be fired when a projectile has to appear in the world
+#[derive(Debug)]
+pub struct SpawnProjectileEvent {
+    pub projectile: Projectile,
+
+    /// World coordinates where the projectile starts
+    pub translation: Vec3,
+
+    pub velocity: Vec2,
+
+    /// Diameter of the projectile
+    pub size: f32,
+}
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        // Collision events are read every frame because they are
+        //  cleared after two frames and steps might be rarer
+        app.add_event::<SpawnProjectileEvent>()
+            .add_system_set(
+                ConditionSet::new()
+                    .run_in_state(ApplicationState::Game)
+                    .with_system(spawn_projectile)
+                    .with_system(projectile_hit_detection)
+                    .into(),
+            )
+            // Lifetime is measured in steps as the flight itself
+            .add_fixed_timestep_system(
+                FIXED_UPDATE,
+                GAMEPLAY_STAGE,
+                projectile_lifetime.run_in_state(ApplicationState::Game),
+            );
+    }
+}
+
+fn spawn_projectile(
+    mut commands: Commands,
+    mut spawn_projectile_event: EventReader<SpawnProjectileEvent>,
+) {
+    for event in spawn_projectile_event.iter() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: PROJECTILE_COLOR,
+                    custom_size: Some(Vec2::splat(event.size)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(event.translation),
+                ..Default::default()
+            })
+            // The projectile doesn't push anything and isn't pulled by the gravity.
+            // It only detects what it touches
+            .insert(RigidBody::Dynamic)
+            .insert(GravityScale(0.0))
+            .insert(LockedAxes::ROTATION_LOCKED)
+            .insert(Sensor)
+            .insert(Collider::ball(event.size / 2.0))
+            .insert(ActiveEvents::COLLISION_EVENTS)
+            .insert(Velocity::linear(event.velocity))
+            // Draw the projectile between physics steps
+            .insert(RenderInterpolation::default())
+            .insert(event.projectile.clone());
+    }
+}
+
+/// Hurts the first `Attackable` entity which the projectile touches.
+/// The projectile disappears after the hit or when it reaches a wall
+#[allow(clippy::type_complexity)]
+fn projectile_hit_detection(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    projectiles_query: Query<(&Projectile, &Velocity)>,
+    walls_query: Query<Entity, Or<(With<Wall>, With<WallCollision>)>>,
+    attackable_query: Query<Entity, With<Attackable>>,
+    enemies_query: Query<Entity, With<Enemy>>,
+    mut damage_event: EventWriter<DamageEvent>,
+) {
+    // The projectile might touch several colliders at the same frame
+    //  but it's already removed after the first one
+    let mut finished_projectiles = HashSet::new();
+
+    for collision in collisions.iter() {
+        if let CollisionEvent::Started(collider_a, collider_b, _) = collision {
+            let hit = find_pair(*collider_a, *collider_b, |entity| {
+                projectiles_query.get(entity).is_ok()
+            });
+
+            let (projectile_entity, target) = match hit {
+                Some(hit) => hit,
+                None => continue,
+            };
+
+            if finished_projectiles.contains(&projectile_entity) {
+                continue;
+            }
+
+            let (projectile, velocity) = projectiles_query
+                .get(projectile_entity)
+                .expect("Projectile must exist");
+
+            // The projectile starts inside the shooter
+            //  and enemies don't hurt each other
+            let friendly = target == projectile.shooter
+                || (enemies_query.get(projectile.shooter).is_ok()
+                    && enemies_query.get(target).is_ok());
+
+            if friendly {
+                continue;
+            }
+
+            if attackable_query.get(target).is_ok() {
+                // The target is pushed in the direction of the flight
+                let knockback = if velocity.linvel.x < 0.0 {
+                    Vec2::new(-projectile.knockback.x, projectile.knockback.y)
+                } else {
+                    projectile.knockback
+                };
+
+                damage_event.send(DamageEvent {
+                    source: projectile.shooter,
+                    target,
+                    amount: projectile.damage,
+                    knockback,
+                });
+            } else if walls_query.get(target).is_err() {
+                // Other colliders (e.g. doors and platforms) don't stop the projectile
+                continue;
+            }
+
+            finished_projectiles.insert(projectile_entity);
+            commands.entity(projectile_entity).despawn();
+        }
+    }
+}
+
+/// Removes projectiles which haven't hit anything in time
+fn projectile_lifetime(
+    mut commands: Commands,
+    mut projectiles_query: Query<(Entity, &mut Projectile)>,
+) {
+    for (projectile_entity, mut projectile) in projectiles_query.iter_mut() {
+        projectile.lifetime = projectile.lifetime.saturating_sub(FIXED_TIMESTEP);
+
+        if projectile.lifetime.is_zero() {
+            commands.entity(projectile_entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod projectile_tests {
+    use std::time::Duration;
+
+    use bevy::ecs::event::Events;
+    use bevy::prelude::*;
+    use bevy_rapier2d::prelude::*;
+    use bevy_rapier2d::rapier::geometry::CollisionEventFlags;
+
+    use crate::common::Attackable;
+    use crate::enemy::Enemy;
+    use crate::fixed_timestep::FIXED_TIMESTEP;
+    use crate::map::WallCollision;
+    use crate::player::Player;
+    use crate::DamageEvent;
+
+    use super::{projectile_hit_detection, projectile_lifetime, Projectile};
+
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        app.add_event::<CollisionEvent>()
+            .add_event::<DamageEvent>()
+            .add_system(projectile_hit_detection)
+            .add_system(projectile_lifetime);
+
+        app
+    }
+
+    fn spawn_projectile(app: &mut App, shooter: Entity, velocity: Vec2) -> Entity {
+        app.world
+            .spawn()
+            .insert(Projectile {
+                shooter,
+                damage: 2,
+                knockback: Vec2::new(100.0, 30.0),
+                lifetime: Duration::from_secs(1),
+            })
+            .insert(Velocity::linear(velocity))
+            .id()
+    }
+
+    fn spawn_enemy(app: &mut App) -> Entity {
+        app.world.spawn().insert(Enemy).insert(Attackable).id()
+    }
+
+    fn touch(app: &mut App, projectile: Entity, target: Entity) {
+        app.world
+            .resource_mut::<Events<CollisionEvent>>()
+            .send(CollisionEvent::Started(
+                projectile,
+                target,
+                CollisionEventFlags::SENSOR,
+            ));
+    }
+
+    fn damage_events(app: &App) -> Vec<(Entity, i32, Vec2)> {
+        let events = app.world.resource::<Events<DamageEvent>>();
+        let mut reader = events.get_reader();
+
+        reader
+            .iter(events)
+            .map(|event| (event.target, event.amount, event.knockback))
+            .collect()
+    }
+
+    #[test]
+    fn should_hurt_the_target_and_disappear() {
+        let mut app = prepare_app();
+        let shooter = spawn_enemy(&mut app);
+        let player = app.world.spawn().insert(Player).insert(Attackable).id();
+        let projectile = spawn_projectile(&mut app, shooter, Vec2::new(-100.0, 0.0));
+
+        touch(&mut app, projectile, player);
+        app.update();
+
+        // The player is pushed to the left as the projectile flies
+        assert_eq!(
+            damage_events(&app),
+            vec![(player, 2, Vec2::new(-100.0, 30.0))]
+        );
+        assert!(app.world.get_entity(projectile).is_none());
+    }
+
+    #[test]
+    fn should_disappear_on_walls_without_damage() {
+        let mut app = prepare_app();
+        let shooter = spawn_enemy(&mut app);
+        let wall = app.world.spawn().insert(WallCollision).id();
+        let projectile = spawn_projectile(&mut app, shooter, Vec2::new(100.0, 0.0));
+
+        touch(&mut app, projectile, wall);
+        app.update();
+
+        assert!(damage_events(&app).is_empty());
+        assert!(app.world.get_entity(projectile).is_none());
+    }
+
+    #[test]
+    fn should_fly_through_the_shooter_and_its_allies() {
+        let mut app = prepare_app();
+        let shooter = spawn_enemy(&mut app);
+        let ally = spawn_enemy(&mut app);
+        let projectile = spawn_projectile(&mut app, shooter, Vec2::new(100.0, 0.0));
+
+        touch(&mut app, projectile, shooter);
+        touch(&mut app, projectile, ally);
+        app.update();
+
+        assert!(damage_events(&app).is_empty());
+        assert!(app.world.get_entity(projectile).is_some());
+    }
+
+    #[test]
+    fn should_fly_through_colliders_which_are_not_walls() {
+        let mut app = prepare_app();
+        let shooter = spawn_enemy(&mut app);
+        let platform = app.world.spawn().id();
+        let projectile = spawn_projectile(&mut app, shooter, Vec2::new(100.0, 0.0));
+
+        touch(&mut app, projectile, platform);
+        app.update();
+
+        assert!(app.world.get_entity(projectile).is_some());
+    }
+
+    #[test]
+    fn should_hit_only_the_first_target() {
+        let mut app = prepare_app();
+        let shooter = app.world.spawn().insert(Player).id();
+        let first_enemy = spawn_enemy(&mut app);
+        let second_enemy = spawn_enemy(&mut app);
+        let projectile = spawn_projectile(&mut app, shooter, Vec2::new(100.0, 0.0));
+
+        touch(&mut app, projectile, first_enemy);
+        touch(&mut app, projectile, second_enemy);
+        app.update();
+
+        assert_eq!(
+            damage_events(&app),
+            vec![(first_enemy, 2, Vec2::new(100.0, 30.0))]
+        );
+    }
+
+    #[test]
+    fn should_disappear_when_the_lifetime_is_over() {
+        let mut app = prepare_app();
+        let shooter = spawn_enemy(&mut app);
+        let projectile = spawn_projectile(&mut app, shooter, Vec2::new(100.0, 0.0));
+
+        // Every update is one step of the lifetime
+        let steps = (Duration::from_secs(1).as_nanos() / FIXED_TIMESTEP.as_nanos()) as usize;
+
+        for _ in 0..steps - 1 {
+            app.update();
+        }
+
+        assert!(app.world.get_entity(projectile).is_some());
+
+        app.update();
+
+        assert!(app.world.get_entity(projectile).is_none());
+    }
+}
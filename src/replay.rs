@@ -0,0 +1,271 @@
+use std::fs::{self, File};
+
+use bevy::{app::AppExit, prelude::*};
+use iyes_loopless::prelude::*;
+use ron::de::from_reader;
+use ron::ser::{to_writer_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    actions::{action_strength, Action},
+    fixed_timestep::{ForcedFrameDuration, FIXED_TIMESTEP},
+    player::Player,
+    ApplicationState,
+};
+
+pub struct ReplayPlugin;
+
+/// Actions which affect the player in the game.
+/// Menu actions are not recorded because menus are controlled by the mouse as well
+const RECORDED_ACTIONS: [Action; 7] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::Jump,
+    Action::Attack,
+    Action::Interact,
+];
+
+/// Describes whether the game is recorded or replayed.
+/// Selected once on startup by `--record <file>` or `--replay <file>`
+///  command line flags
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReplayMode {
+    /// The game is played as usual
+    Off,
+
+    /// Actions are saved into the file while the game is played
+    Record(String),
+
+    /// Actions are taken from the file instead of the keyboard and gamepads
+    Replay(String),
+}
+
+impl ReplayMode {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().collect();
+
+        for (index, arg) in args.iter().enumerate() {
+            let path = || {
+                args.get(index + 1)
+                    .cloned()
+                    .unwrap_or_else(|| panic!("{} flag requires a file path", arg))
+            };
+
+            match arg.as_str() {
+                "--record" => return Self::Record(path()),
+                "--replay" => return Self::Replay(path()),
+                _ => (),
+            }
+        }
+
+        Self::Off
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self != Self::Off
+    }
+}
+
+/// Pressed actions during one frame of the game
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct ReplayFrame {
+    /// Pressed actions with their strength
+    pub actions: Vec<(Action, f32)>,
+}
+
+/// Describes the whole recorded game
+///
+/// Note: Works with file system. There is no seed because
+///  the game has no randomness yet. Random loot or AI
+///  must add the seed back to repeat the game exactly
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct Replay {
+    /// Frames are recorded only while the player is in the game
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn load(path: &str) -> Self {
+        let file = File::open(path).expect("Unable to open the replay");
+
+        from_reader(file).expect("Unable to load the replay")
+    }
+
+    pub fn save(&self, path: &str) {
+        if let Some(directory) = std::path::Path::new(path).parent() {
+            fs::create_dir_all(directory).expect("Unable to create a directory");
+        }
+
+        let file = File::create(path).expect("Cannot create a file");
+
+        to_writer_pretty(&file, &self, PrettyConfig::default()).expect("Must save the replay");
+    }
+}
+
+/// The recording is saved every time when this amount of frames is recorded
+///  to keep the most of it if the game crashes
+const FLUSH_FRAMES: usize = 256;
+
+/// Exists while the game is recorded
+pub struct ReplayRecorder {
+    pub path: String,
+    pub replay: Replay,
+}
+
+/// Exists while the game is replayed
+pub struct ReplayPlayback {
+    pub replay: Replay,
+
+    /// Index of the next frame which will be replayed
+    pub frame: usize,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, frame: 0 }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.replay.frames.len()
+    }
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(load_replay)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                record_frame
+                    .run_in_state(ApplicationState::Game)
+                    .run_if_resource_exists::<ReplayRecorder>()
+                    .after("update_actions"),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                replay_frame
+                    .run_in_state(ApplicationState::Game)
+                    .run_if_resource_exists::<ReplayPlayback>()
+                    .label("replay_actions")
+                    .after("update_actions"),
+            )
+            .add_system_to_stage(
+                CoreStage::Last,
+                save_recording.run_if_resource_exists::<ReplayRecorder>(),
+            );
+    }
+}
+
+fn load_replay(
+    mut commands: Commands,
+    replay_mode: Res<ReplayMode>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    // Every frame makes exactly one step of the game
+    //  to repeat physics and timers as they have been recorded
+    if replay_mode.is_active() || playback.is_some() {
+        commands.insert_resource(ForcedFrameDuration(FIXED_TIMESTEP));
+    }
+
+    // The playback might be prepared without the file (e.g. in tests)
+    if playback.is_some() {
+        return;
+    }
+
+    match &*replay_mode {
+        ReplayMode::Off => (),
+        ReplayMode::Record(path) => {
+            commands.insert_resource(ReplayRecorder {
+                path: path.clone(),
+                replay: Replay::default(),
+            });
+        }
+        ReplayMode::Replay(path) => {
+            commands.insert_resource(ReplayPlayback::new(Replay::load(path)));
+        }
+    }
+}
+
+fn record_frame(
+    actions: Res<Input<Action>>,
+    axes: Res<Axis<Action>>,
+    mut recorder: ResMut<ReplayRecorder>,
+    player_query: Query<With<Player>>,
+) {
+    // Frames are counted from the moment when the player appears
+    //  because the map is loaded for a different time
+    if player_query.is_empty() {
+        return;
+    }
+
+    let actions = RECORDED_ACTIONS
+        .into_iter()
+        .filter(|action| actions.pressed(*action))
+        .map(|action| (action, action_strength(&actions, &axes, action)))
+        .collect();
+
+    recorder.replay.frames.push(ReplayFrame { actions });
+}
+
+/// Overrides recorded actions by the next frame of the replay.
+/// Other actions (e.g. pause) are still taken from the keyboard and gamepads
+fn replay_frame(
+    mut actions: ResMut<Input<Action>>,
+    mut axes: ResMut<Axis<Action>>,
+    mut playback: ResMut<ReplayPlayback>,
+    player_query: Query<With<Player>>,
+) {
+    if player_query.is_empty() || playback.is_finished() {
+        return;
+    }
+
+    let previous_frame = playback
+        .frame
+        .checked_sub(1)
+        .map(|index| playback.replay.frames[index].clone())
+        .unwrap_or_default();
+    let frame = &playback.replay.frames[playback.frame];
+
+    for action in RECORDED_ACTIONS {
+        let was_pressed = previous_frame.actions.iter().any(|(a, _)| *a == action);
+        let strength = frame
+            .actions
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, strength)| *strength);
+
+        // Restore `just_pressed` and `just_released` relative to the previous frame
+        //  of the replay instead of the real keyboard
+        actions.reset(action);
+
+        if was_pressed || strength.is_some() {
+            actions.press(action);
+        }
+
+        if was_pressed {
+            actions.clear_just_pressed(action);
+        }
+
+        if was_pressed && strength.is_none() {
+            actions.release(action);
+        }
+
+        axes.set(action, strength.unwrap_or(0.0));
+    }
+
+    playback.frame += 1;
+
+    if playback.is_finished() {
+        info!("The replay is finished");
+    }
+}
+
+fn save_recording(recorder: Res<ReplayRecorder>, mut app_exit_events: EventReader<AppExit>) {
+    let frames = recorder.replay.frames.len();
+    let is_flush_frame = recorder.is_changed() && frames > 0 && frames % FLUSH_FRAMES == 0;
+
+    if app_exit_events.iter().next().is_some() || is_flush_frame {
+        recorder.replay.save(&recorder.path);
+    }
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use ron::de::from_reader;
+use serde::Deserialize;
+use std::fs::File;
+
+pub struct CombatConfigPlugin;
+
+impl Plugin for CombatConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.insert_resource(CombatConfig::load());
+}
+
+/// Describes how hard the entity hits
+#[derive(Clone, Deserialize, Debug)]
+pub struct AttackerConfig {
+    /// How many health points the target loses
+    pub damage: i32,
+
+    /// Impulse which pushes the target when it's on the right side.
+    /// It's mirrored for targets on the left side
+    pub knockback: Vec2,
+}
+
+/// Describes what happens after the player receives a hit
+#[derive(Clone, Deserialize, Debug)]
+pub struct InvulnerabilityConfig {
+    /// How many seconds the player doesn't receive any damage
+    pub duration: f32,
+
+    /// The player passes through enemies while invulnerable
+    pub pass_through_enemies: bool,
+}
+
+/// Describes what happens when the player jumps on the enemy
+#[derive(Clone, Deserialize, Debug)]
+pub struct StompConfig {
+    /// Impulse which bounces the player up from the stomped enemy
+    pub bounce: f32,
+}
+
+/// Damage and knockback of the player and what happens after hits.
+/// Enemies are described by their archetypes
+///
+/// Note: Loaded from `resources/combat.ron` file
+#[derive(Clone, Deserialize, Debug)]
+pub struct CombatConfig {
+    pub player: AttackerConfig,
+    pub invulnerability: InvulnerabilityConfig,
+    pub stomp: StompConfig,
+}
+
+impl CombatConfig {
+    pub fn load() -> Self {
+        let file = File::open("resources/combat.ron").expect("Failed opening file");
+
+        from_reader(file).expect("Unable to parse the combat config")
+    }
+}
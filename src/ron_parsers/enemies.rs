@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use ron::de::from_reader;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+
+use crate::enemy::EnemyType;
+
+use super::AttackerConfig;
+
+pub struct EnemiesConfigPlugin;
+
+impl Plugin for EnemiesConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.insert_resource(EnemiesConfig::load());
+}
+
+/// Describes how the enemy reacts on the player
+#[derive(Clone, Deserialize, Debug)]
+pub struct BehaviourConfig {
+    /// Distance from which the enemy notices the player
+    ///  if nothing blocks the line of sight
+    pub sight_range: f32,
+
+    /// Seconds after which the enemy stops chasing the unseen player
+    ///  and returns to the patrol
+    pub forget_time: f32,
+
+    /// Speed of the enemy while it chases the player
+    pub chase_speed: f32,
+
+    /// Distance from which the enemy starts the attack
+    pub attack_range: f32,
+
+    /// Seconds between the start of the attack and the hit.
+    /// The player has this time to step away
+    pub wind_up: f32,
+
+    /// Seconds after the hit until the next attack
+    pub attack_cooldown: f32,
+
+    /// The enemy runs away from the player when its health is lower or equal.
+    /// Zero means that the enemy never flees
+    pub flee_health: i32,
+}
+
+/// Describes what the ranged enemy shoots
+#[derive(Clone, Deserialize, Debug)]
+pub struct ProjectileConfig {
+    pub speed: f32,
+
+    /// Seconds after which the missed projectile disappears
+    pub lifetime: f32,
+
+    /// Diameter of the projectile
+    pub size: f32,
+}
+
+/// Describes one kind of enemies.
+/// A new enemy needs only a new archetype, its sprite sheets in `resources/textures.ron`
+///  and a new `enemy_type` value in the LDtk map
+#[derive(Clone, Deserialize, Debug)]
+pub struct EnemyArchetype {
+    /// Value of the `enemy_type` field in the LDtk map
+    pub enemy_type: EnemyType,
+
+    /// Size of the collider (not the half size)
+    pub collider_size: Vec2,
+
+    pub health: i32,
+    pub speed: f32,
+    pub attacker: AttackerConfig,
+
+    /// Name of the behaviour from `ai_profiles`
+    pub ai_profile: String,
+
+    /// Ranged enemies shoot projectiles instead of hitting in melee
+    #[serde(default)]
+    pub projectile: Option<ProjectileConfig>,
+}
+
+/// All enemy archetypes and behaviours which they share
+///
+/// Note: Loaded from `resources/enemies.ron` file
+#[derive(Clone, Deserialize, Debug)]
+pub struct EnemiesConfig {
+    pub ai_profiles: HashMap<String, BehaviourConfig>,
+    pub archetypes: Vec<EnemyArchetype>,
+}
+
+impl EnemiesConfig {
+    pub fn load() -> Self {
+        let file = File::open("resources/enemies.ron").expect("Failed opening file");
+
+        from_reader(file).expect("Unable to parse the enemies config")
+    }
+
+    /// Returns the archetype of the specific enemy type
+    pub fn archetype(&self, enemy_type: &EnemyType) -> &EnemyArchetype {
+        self.archetypes
+            .iter()
+            .find(|archetype| archetype.enemy_type == *enemy_type)
+            .unwrap_or_else(|| panic!("Enemies config must describe {:?} enemy", enemy_type))
+    }
+
+    /// Returns the behaviour of the archetype
+    pub fn behaviour(&self, archetype: &EnemyArchetype) -> &BehaviourConfig {
+        self.ai_profiles
+            .get(&archetype.ai_profile)
+            .unwrap_or_else(|| panic!("AI profile {:?} must be described", archetype.ai_profile))
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use ron::de::from_reader;
+use serde::Deserialize;
+use std::fs::File;
+
+use crate::items::{EquipmentSlot, ItemType, StatModifiers};
+
+pub struct ItemsConfigPlugin;
+
+impl Plugin for ItemsConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.insert_resource(ItemsConfig::load());
+}
+
+/// Describes the health regeneration which an item gives
+#[derive(Clone, Deserialize, Debug)]
+pub struct RegenerationConfig {
+    /// How many health points will be restored on every tick
+    pub amount: i32,
+
+    /// Seconds between ticks
+    pub interval: f32,
+
+    /// How many seconds the regeneration lasts
+    pub duration: f32,
+}
+
+/// Describes what happens when the player uses an item
+#[derive(Clone, Deserialize, Debug)]
+pub struct ItemEffectConfig {
+    pub item: ItemType,
+
+    /// How many health points will be restored immediately
+    pub heal: i32,
+
+    pub regeneration: Option<RegenerationConfig>,
+}
+
+/// Describes the item which might be equipped by the player
+#[derive(Clone, Deserialize, Debug)]
+pub struct EquipmentConfig {
+    pub item: ItemType,
+    pub slot: EquipmentSlot,
+    pub modifiers: StatModifiers,
+}
+
+/// All items effects and equipment
+///
+/// Note: Loaded from `resources/items.ron` file. Only items described
+///  in this file might be consumed or equipped by the player
+#[derive(Clone, Deserialize, Debug)]
+pub struct ItemsConfig {
+    pub effects: Vec<ItemEffectConfig>,
+    pub equipment: Vec<EquipmentConfig>,
+}
+
+impl ItemsConfig {
+    pub fn load() -> Self {
+        let file = File::open("resources/items.ron").expect("Failed opening file");
+
+        from_reader(file).expect("Unable to parse the items config")
+    }
+
+    /// Returns an effect of the specific item if the item might be consumed
+    pub fn effect(&self, item: ItemType) -> Option<&ItemEffectConfig> {
+        self.effects.iter().find(|effect| effect.item == item)
+    }
+
+    /// Returns an equipment config of the specific item if the item might be equipped
+    pub fn equipment(&self, item: ItemType) -> Option<&EquipmentConfig> {
+        self.equipment
+            .iter()
+            .find(|equipment| equipment.item == item)
+    }
+}
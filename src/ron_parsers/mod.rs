@@ -1,16 +1,32 @@
 use bevy::prelude::*;
 
+mod combat;
+mod enemies;
+mod items;
 mod settings;
 mod textures;
 
+pub use combat::{AttackerConfig, CombatConfig};
+pub use enemies::{BehaviourConfig, EnemiesConfig, ProjectileConfig};
+pub use items::ItemsConfig;
 pub use settings::Settings;
 pub use textures::{EnemiesSprites, GameTextures, PlayerSprites, SpriteAssetInfo, TutorialSprites};
 
+#[cfg(test)]
+pub use combat::{InvulnerabilityConfig, StompConfig};
+#[cfg(test)]
+pub use settings::{Audio, Controls};
+#[cfg(test)]
+pub use textures::{EnemySprites, ItemsSprites};
+
 pub struct RonParsersPlugin;
 
 impl Plugin for RonParsersPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(settings::SettingsPlugin)
+            .add_plugin(combat::CombatConfigPlugin)
+            .add_plugin(enemies::EnemiesConfigPlugin)
+            .add_plugin(items::ItemsConfigPlugin)
             .add_plugin(textures::TexturesPlugin);
     }
 }
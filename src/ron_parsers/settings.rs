@@ -2,8 +2,11 @@ use bevy::prelude::*;
 use ron::de::from_reader;
 use ron::ser::to_writer;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, File};
 
+use crate::actions::Action;
+
 pub struct SettingsPlugin;
 
 impl Plugin for SettingsPlugin {
@@ -31,12 +34,95 @@ pub struct Audio {
     pub volume: i8,
 }
 
+/// Keys and gamepad buttons which trigger actions
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct Controls {
+    pub bindings: HashMap<Action, KeyCode>,
+
+    #[serde(default = "Controls::default_gamepad_bindings")]
+    pub gamepad_bindings: HashMap<Action, GamepadButtonType>,
+}
+
+impl Controls {
+    pub fn key(&self, action: Action) -> Option<KeyCode> {
+        self.bindings.get(&action).copied()
+    }
+
+    pub fn gamepad_button(&self, action: Action) -> Option<GamepadButtonType> {
+        self.gamepad_bindings.get(&action).copied()
+    }
+
+    /// Settings saved before new actions have appeared don't contain them.
+    /// Such actions get their default bindings
+    fn add_missing_bindings(&mut self) {
+        let defaults = Self::default();
+
+        for (action, key) in defaults.bindings {
+            self.bindings.entry(action).or_insert(key);
+        }
+
+        for (action, button) in defaults.gamepad_bindings {
+            self.gamepad_bindings.entry(action).or_insert(button);
+        }
+    }
+
+    /// Movement is bound to the left stick as well
+    fn default_gamepad_bindings() -> HashMap<Action, GamepadButtonType> {
+        HashMap::from([
+            (Action::MoveLeft, GamepadButtonType::DPadLeft),
+            (Action::MoveRight, GamepadButtonType::DPadRight),
+            (Action::MoveUp, GamepadButtonType::DPadUp),
+            (Action::MoveDown, GamepadButtonType::DPadDown),
+            (Action::Jump, GamepadButtonType::South),
+            (Action::Attack, GamepadButtonType::West),
+            (Action::Interact, GamepadButtonType::East),
+            (Action::Inventory, GamepadButtonType::North),
+            (Action::Pause, GamepadButtonType::Start),
+            (Action::MenuUp, GamepadButtonType::DPadUp),
+            (Action::MenuDown, GamepadButtonType::DPadDown),
+            (Action::MenuLeft, GamepadButtonType::DPadLeft),
+            (Action::MenuRight, GamepadButtonType::DPadRight),
+            (Action::MenuSelect, GamepadButtonType::South),
+            (Action::MenuBack, GamepadButtonType::East),
+        ])
+    }
+}
+
+/// Used when user settings have been saved before controls appeared
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::from([
+                (Action::MoveLeft, KeyCode::Left),
+                (Action::MoveRight, KeyCode::Right),
+                (Action::MoveUp, KeyCode::Up),
+                (Action::MoveDown, KeyCode::Down),
+                (Action::Jump, KeyCode::Space),
+                (Action::Attack, KeyCode::LShift),
+                (Action::Interact, KeyCode::E),
+                (Action::Inventory, KeyCode::I),
+                (Action::Pause, KeyCode::Escape),
+                (Action::MenuUp, KeyCode::Up),
+                (Action::MenuDown, KeyCode::Down),
+                (Action::MenuLeft, KeyCode::Left),
+                (Action::MenuRight, KeyCode::Right),
+                (Action::MenuSelect, KeyCode::Return),
+                (Action::MenuBack, KeyCode::Escape),
+            ]),
+            gamepad_bindings: Self::default_gamepad_bindings(),
+        }
+    }
+}
+
 /// All user settings
 ///
 /// Note: Works with file system
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct Settings {
     pub audio: Audio,
+
+    #[serde(default)]
+    pub controls: Controls,
 }
 
 impl Settings {
@@ -58,7 +144,12 @@ impl Settings {
                 // Note: We should validate current settigs and default settings
                 //  we may have different configs even if we have `current_settigs` file
                 //  when we update something in default config as an example
-                from_reader(current_settings).expect("Unable to load settings")
+                let mut settings: Self =
+                    from_reader(current_settings).expect("Unable to load settings");
+
+                settings.controls.add_missing_bindings();
+
+                settings
             }
             // There is no current settings yet, then we have to create it
             Err(_) => {
@@ -88,3 +179,45 @@ impl Settings {
         to_writer(&current_settings_file, &self).expect("Must save settings");
     }
 }
+
+#[cfg(test)]
+mod settings_tests {
+    use bevy::prelude::*;
+    use ron::de::from_str;
+
+    use crate::actions::Action;
+
+    use super::Settings;
+
+    #[test]
+    fn should_add_missing_bindings_to_old_settings() {
+        let mut settings: Settings = from_str(
+            "(
+                audio: (state: true, volume: 5),
+                controls: (
+                    bindings: {Jump: W, Pause: Escape},
+                    gamepad_bindings: {Jump: East},
+                ),
+            )",
+        )
+        .expect("Old settings must be parsed");
+
+        settings.controls.add_missing_bindings();
+
+        // Rebound actions keep their keys
+        assert_eq!(settings.controls.key(Action::Jump), Some(KeyCode::W));
+        assert_eq!(
+            settings.controls.gamepad_button(Action::Jump),
+            Some(GamepadButtonType::East)
+        );
+
+        assert_eq!(
+            settings.controls.key(Action::MenuBack),
+            Some(KeyCode::Escape)
+        );
+        assert_eq!(
+            settings.controls.gamepad_button(Action::MenuLeft),
+            Some(GamepadButtonType::DPadLeft)
+        );
+    }
+}
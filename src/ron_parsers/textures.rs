@@ -1,16 +1,17 @@
 use bevy::prelude::*;
 use ron::de::from_reader;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs::File;
 
+use crate::common::SwingFrames;
+use crate::enemy::{EnemyAnimationState, EnemyType};
+
 /// Describes the sprite assets information
 ///
 /// !!Note!! Works only with TextureAtlas
 #[derive(Clone, Debug)]
 pub struct SpriteAssetInfo {
-    /// The `width` of the sprite cell (not the whole atlas texture)
-    pub width: f32,
-
     /// The `height` of the sprite cell (not the whole atlas texture)
     pub height: f32,
 
@@ -31,12 +32,68 @@ pub struct PlayerSprites {
     pub double_jump: SpriteAssetInfo,
     pub attack: SpriteAssetInfo,
     pub wall_slide: SpriteAssetInfo,
+
+    /// Parts of the swing on the attack sheet
+    pub attack_swing: SwingFrames,
 }
 
+/// Sprite sheets of one enemy archetype
+#[derive(Debug)]
+pub struct EnemySprites {
+    pub idle: SpriteAssetInfo,
+    pub walk: SpriteAssetInfo,
+    pub hurt: SpriteAssetInfo,
+    pub attack: SpriteAssetInfo,
+    pub death: SpriteAssetInfo,
+}
+
+impl EnemySprites {
+    /// Returns the sprite sheet of the animation state
+    pub fn get(&self, state: EnemyAnimationState) -> &SpriteAssetInfo {
+        match state {
+            EnemyAnimationState::Idle => &self.idle,
+            EnemyAnimationState::Walk => &self.walk,
+            EnemyAnimationState::Hurt => &self.hurt,
+            EnemyAnimationState::Attack => &self.attack,
+            EnemyAnimationState::Death => &self.death,
+        }
+    }
+}
+
+/// Sprite sheets of all enemy archetypes
 #[derive(Debug)]
 pub struct EnemiesSprites {
-    pub gray: SpriteAssetInfo,
-    pub long: SpriteAssetInfo,
+    pub sprites: HashMap<EnemyType, EnemySprites>,
+}
+
+impl EnemiesSprites {
+    pub fn get(&self, enemy_type: &EnemyType) -> &EnemySprites {
+        self.sprites
+            .get(enemy_type)
+            .unwrap_or_else(|| panic!("Sprite sheets of {:?} enemy must be loaded", enemy_type))
+    }
+}
+
+/// Contains the whole items sheet and indexes
+///  of the specific icons inside this sheet
+#[derive(Debug)]
+pub struct ItemsSprites {
+    /// TextureAtlas with all icons
+    pub atlas: SpriteAssetInfo,
+
+    pub knife: usize,
+    pub healing_plant: usize,
+    pub meat: usize,
+    pub boots: usize,
+    pub water: usize,
+    pub gem: usize,
+    pub scraps: usize,
+    pub battery: usize,
+    pub food: usize,
+    pub medkit: usize,
+    pub power_orb: usize,
+    pub key: usize,
+    pub chest: usize,
 }
 
 #[derive(Debug)]
@@ -80,9 +137,20 @@ enum DeserializedPlayerType {
 }
 
 #[derive(Clone, Deserialize, Debug, PartialEq, Eq)]
-enum DeserializedEnemyType {
-    Gray,
-    Long,
+enum DeserializedItemType {
+    Knife,
+    HealingPlant,
+    Meat,
+    Boots,
+    Water,
+    Gem,
+    Scraps,
+    Battery,
+    Food,
+    Medkit,
+    PowerOrb,
+    Key,
+    Chest,
 }
 
 #[derive(Clone, Deserialize, Debug, PartialEq, Eq)]
@@ -99,16 +167,45 @@ struct DeserializedPlayerSpriteInfo {
     pub texture_path: String,
     pub items: usize,
     pub column_size: usize,
+
+    /// Parts of the swing. Required only for the attack sheet
+    #[serde(default)]
+    pub swing: Option<SwingFrames>,
 }
 
 #[derive(Clone, Deserialize, Debug)]
 struct DeserializedEnemySpriteInfo {
-    pub sprite_type: DeserializedEnemyType,
+    pub sprite_type: EnemyAnimationState,
+    pub width: f32,
+    pub height: f32,
+    pub texture_path: String,
+    pub items: usize,
+    pub column_size: usize,
+}
+
+/// Sprite sheets of the enemy archetype with the same `enemy_type`
+#[derive(Clone, Deserialize, Debug)]
+struct DeserializedEnemySprites {
+    pub enemy_type: EnemyType,
+    pub sheets: Vec<DeserializedEnemySpriteInfo>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct DeserializedItemIconInfo {
+    pub sprite_type: DeserializedItemType,
+
+    /// Index of the icon inside the items sheet
+    pub index: usize,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct DeserializedItemsSpriteInfo {
     pub width: f32,
     pub height: f32,
     pub texture_path: String,
     pub items: usize,
     pub column_size: usize,
+    pub icons: Vec<DeserializedItemIconInfo>,
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -120,7 +217,8 @@ struct DeserializedTutorialSpriteInfo {
 #[derive(Clone, Deserialize, Debug)]
 struct DeserializedGameTextures {
     pub player: Vec<DeserializedPlayerSpriteInfo>,
-    pub enemies: Vec<DeserializedEnemySpriteInfo>,
+    pub enemies: Vec<DeserializedEnemySprites>,
+    pub items: DeserializedItemsSpriteInfo,
     pub tutorials: Vec<DeserializedTutorialSpriteInfo>,
 }
 
@@ -136,6 +234,7 @@ impl DeserializedGameTextures {
 pub struct GameTextures {
     pub player: PlayerSprites,
     pub enemies: EnemiesSprites,
+    pub items: ItemsSprites,
     pub tutorials: TutorialSprites,
 }
 
@@ -148,11 +247,13 @@ impl GameTextures {
         let texture_alias = &mut texture_atlases;
         let player_textures = &deserialized_textures.player;
         let enemy_textures = &deserialized_textures.enemies;
+        let item_textures = &deserialized_textures.items;
         let tutorial_textures = &deserialized_textures.tutorials;
 
         Self {
             player: Self::prepare_player_textures(player_textures, &asset_server, texture_alias),
             enemies: Self::prepare_enemies_textures(enemy_textures, &asset_server, texture_alias),
+            items: Self::prepare_items_textures(item_textures, &asset_server, texture_alias),
             tutorials: Self::prepare_tutorial_textures(tutorial_textures, &asset_server),
         }
     }
@@ -173,6 +274,7 @@ impl GameTextures {
         let mut death = None;
         let mut attack = None;
         let mut wall_slide = None;
+        let mut attack_swing = None;
 
         for texture in player_textures.iter() {
             let player_texture = asset_server.load(texture.texture_path.as_str());
@@ -189,7 +291,6 @@ impl GameTextures {
             );
 
             let sprite_asset_info = SpriteAssetInfo {
-                width: texture.width,
                 height: texture.height,
                 texture: texture_atlases.add(player_atlas),
                 items: texture.items,
@@ -218,7 +319,16 @@ impl GameTextures {
                     death = Some(sprite_asset_info);
                 }
                 DeserializedPlayerType::Attack => {
+                    let swing = texture
+                        .swing
+                        .expect("Attack sheet of the player must describe the swing");
+
+                    if swing.total() != texture.items {
+                        panic!("Swing frames must cover the whole attack sheet of the player");
+                    }
+
                     attack = Some(sprite_asset_info);
+                    attack_swing = Some(swing);
                 }
                 DeserializedPlayerType::WallSlide => {
                     wall_slide = Some(sprite_asset_info);
@@ -250,52 +360,196 @@ impl GameTextures {
             death: death.unwrap(),
             attack: attack.unwrap(),
             wall_slide: wall_slide.unwrap(),
+            attack_swing: attack_swing.unwrap(),
         }
     }
 
     fn prepare_enemies_textures(
-        enemy_textures: &[DeserializedEnemySpriteInfo],
+        enemy_textures: &[DeserializedEnemySprites],
         asset_server: &Res<AssetServer>,
         texture_atlases: &mut ResMut<Assets<TextureAtlas>>,
     ) -> EnemiesSprites {
-        let mut gray = None;
-        let mut long = None;
+        let mut sprites = HashMap::new();
+
+        for enemy in enemy_textures.iter() {
+            let mut idle = None;
+            let mut walk = None;
+            let mut hurt = None;
+            let mut attack = None;
+            let mut death = None;
+
+            for texture in enemy.sheets.iter() {
+                let enemy_texture = asset_server.load(texture.texture_path.as_str());
+                let enemy_atlas = TextureAtlas::from_grid_with_padding(
+                    enemy_texture,
+                    Vec2::new(texture.width, texture.height),
+                    texture.column_size,
+                    texture.items / texture.column_size,
+                    Vec2::new(0.0, 0.0),
+                    Vec2::ZERO,
+                );
+
+                let sprite_asset_info = SpriteAssetInfo {
+                    height: texture.height,
+                    texture: texture_atlases.add(enemy_atlas),
+                    items: texture.items,
+                };
+
+                match texture.sprite_type {
+                    EnemyAnimationState::Idle => {
+                        idle = Some(sprite_asset_info);
+                    }
+                    EnemyAnimationState::Walk => {
+                        walk = Some(sprite_asset_info);
+                    }
+                    EnemyAnimationState::Hurt => {
+                        hurt = Some(sprite_asset_info);
+                    }
+                    EnemyAnimationState::Attack => {
+                        attack = Some(sprite_asset_info);
+                    }
+                    EnemyAnimationState::Death => {
+                        death = Some(sprite_asset_info);
+                    }
+                }
+            }
 
-        for texture in enemy_textures.iter() {
-            let enemy_texture = asset_server.load(texture.texture_path.as_str());
-            let enemy_atlas = TextureAtlas::from_grid_with_padding(
-                enemy_texture,
-                Vec2::new(texture.width, texture.height),
-                texture.column_size,
-                texture.items / texture.column_size,
-                Vec2::new(0.0, 0.0),
-                Vec2::ZERO,
-            );
+            match (idle, walk, hurt, attack, death) {
+                (Some(idle), Some(walk), Some(hurt), Some(attack), Some(death)) => {
+                    sprites.insert(
+                        enemy.enemy_type.clone(),
+                        EnemySprites {
+                            idle,
+                            walk,
+                            hurt,
+                            attack,
+                            death,
+                        },
+                    );
+                }
+                _ => {
+                    panic!(
+                        "All animations of {:?} enemy must be mapped",
+                        enemy.enemy_type
+                    );
+                }
+            }
+        }
 
-            let sprite_asset_info = SpriteAssetInfo {
-                width: texture.width,
-                height: texture.height,
-                texture: texture_atlases.add(enemy_atlas),
-                items: texture.items,
-            };
+        EnemiesSprites { sprites }
+    }
 
-            match texture.sprite_type {
-                DeserializedEnemyType::Gray => {
-                    gray = Some(sprite_asset_info);
+    fn prepare_items_textures(
+        item_textures: &DeserializedItemsSpriteInfo,
+        asset_server: &Res<AssetServer>,
+        texture_atlases: &mut ResMut<Assets<TextureAtlas>>,
+    ) -> ItemsSprites {
+        let items_texture = asset_server.load(item_textures.texture_path.as_str());
+        let items_atlas = TextureAtlas::from_grid_with_padding(
+            items_texture,
+            Vec2::new(item_textures.width, item_textures.height),
+            item_textures.column_size,
+            item_textures.items / item_textures.column_size,
+            Vec2::ZERO,
+            Vec2::ZERO,
+        );
+
+        let atlas = SpriteAssetInfo {
+            height: item_textures.height,
+            texture: texture_atlases.add(items_atlas),
+            items: item_textures.items,
+        };
+
+        let mut knife = None;
+        let mut healing_plant = None;
+        let mut meat = None;
+        let mut boots = None;
+        let mut water = None;
+        let mut gem = None;
+        let mut scraps = None;
+        let mut battery = None;
+        let mut food = None;
+        let mut medkit = None;
+        let mut power_orb = None;
+        let mut key = None;
+        let mut chest = None;
+
+        for icon in item_textures.icons.iter() {
+            match icon.sprite_type {
+                DeserializedItemType::Knife => {
+                    knife = Some(icon.index);
+                }
+                DeserializedItemType::HealingPlant => {
+                    healing_plant = Some(icon.index);
+                }
+                DeserializedItemType::Meat => {
+                    meat = Some(icon.index);
+                }
+                DeserializedItemType::Boots => {
+                    boots = Some(icon.index);
+                }
+                DeserializedItemType::Water => {
+                    water = Some(icon.index);
                 }
-                DeserializedEnemyType::Long => {
-                    long = Some(sprite_asset_info);
+                DeserializedItemType::Gem => {
+                    gem = Some(icon.index);
+                }
+                DeserializedItemType::Scraps => {
+                    scraps = Some(icon.index);
+                }
+                DeserializedItemType::Battery => {
+                    battery = Some(icon.index);
+                }
+                DeserializedItemType::Food => {
+                    food = Some(icon.index);
+                }
+                DeserializedItemType::Medkit => {
+                    medkit = Some(icon.index);
+                }
+                DeserializedItemType::PowerOrb => {
+                    power_orb = Some(icon.index);
+                }
+                DeserializedItemType::Key => {
+                    key = Some(icon.index);
+                }
+                DeserializedItemType::Chest => {
+                    chest = Some(icon.index);
                 }
             }
         }
 
-        if gray.is_none() || long.is_none() {
-            panic!("All enemies animations must be mapped");
+        if knife.is_none()
+            || healing_plant.is_none()
+            || meat.is_none()
+            || boots.is_none()
+            || water.is_none()
+            || gem.is_none()
+            || scraps.is_none()
+            || battery.is_none()
+            || food.is_none()
+            || medkit.is_none()
+            || power_orb.is_none()
+            || key.is_none()
+            || chest.is_none()
+        {
+            panic!("All items icons must be mapped");
         }
 
-        EnemiesSprites {
-            gray: gray.unwrap(),
-            long: long.unwrap(),
+        ItemsSprites {
+            atlas,
+            knife: knife.unwrap(),
+            healing_plant: healing_plant.unwrap(),
+            meat: meat.unwrap(),
+            boots: boots.unwrap(),
+            water: water.unwrap(),
+            gem: gem.unwrap(),
+            scraps: scraps.unwrap(),
+            battery: battery.unwrap(),
+            food: food.unwrap(),
+            medkit: medkit.unwrap(),
+            power_orb: power_orb.unwrap(),
+            key: key.unwrap(),
+            chest: chest.unwrap(),
         }
     }
 
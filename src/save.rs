@@ -0,0 +1,422 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use iyes_loopless::prelude::*;
+use ron::de::from_reader;
+use ron::ser::{to_writer_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::Health,
+    items::{Equipment, Inventory, ItemStack, ItemType},
+    ldtk::respawn_world,
+    level_transition::LevelEntry,
+    player::Player,
+    ron_parsers::ItemsConfig,
+    ApplicationState, GameMode, PauseTheGameEvent, ResumeTheGameEvent,
+};
+
+pub struct SavePlugin;
+
+/// How many save slots the player has
+pub const SAVE_SLOTS: usize = 3;
+
+/// Contains everything which the player has changed on the map.
+/// LDtk respawns entities every time when the level is loaded
+///  so spawn systems have to check it
+#[derive(Default, Debug, Clone)]
+pub struct GameProgress {
+    /// LDtk iids of killed enemies
+    pub killed_enemies: HashSet<String>,
+
+    /// LDtk iids of opened chests
+    pub opened_chests: HashSet<String>,
+}
+
+/// The slot which is used by autosave and the last loaded game
+pub struct CurrentSaveSlot(pub usize);
+
+/// Should be fired when the game has to be saved
+/// Accepts the slot index
+#[derive(Debug)]
+pub struct SaveGameEvent(pub usize);
+
+/// Should be fired when the game has to be loaded
+/// Accepts the slot index
+#[derive(Debug)]
+pub struct LoadGameEvent(pub usize);
+
+/// The loaded game which is applied as soon as the player is ready.
+/// On a fresh launch the player gets all components
+///  only when the game is started
+struct PendingSave(SaveData);
+
+/// Describes the whole saved game
+///
+/// Note: Works with file system
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct SaveData {
+    /// LDtk iid of the level where the player has been
+    pub level_iid: String,
+
+    /// Player world coordinates
+    pub position: (f32, f32),
+
+    pub health: Health,
+    pub items: Vec<ItemStack>,
+    pub equipment: Vec<ItemType>,
+    pub killed_enemies: Vec<String>,
+    pub opened_chests: Vec<String>,
+}
+
+impl SaveData {
+    /// Every game mode has its own map that's why they
+    ///  can't share the same saves
+    fn slot_path(slot: usize, game_mode: GameMode) -> String {
+        let mode_directory = match game_mode {
+            GameMode::Platformer => "platformer",
+            GameMode::TopDown => "top_down",
+        };
+
+        format!("temporary/saves/{}/slot_{}.ron", mode_directory, slot)
+    }
+
+    /// Returns the saved game or `None` if the slot is empty
+    pub fn load(slot: usize, game_mode: GameMode) -> Option<Self> {
+        Self::load_from(&Self::slot_path(slot, game_mode))
+    }
+
+    /// Returns `None` if the file doesn't exist or it's corrupted
+    fn load_from(path: &str) -> Option<Self> {
+        let file = File::open(path).ok()?;
+
+        match from_reader(file) {
+            Ok(save_data) => Some(save_data),
+            Err(error) => {
+                error!("Unable to load the save {}: {}", path, error);
+
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, slot: usize, game_mode: GameMode) {
+        self.save_to(&Self::slot_path(slot, game_mode));
+    }
+
+    fn save_to(&self, path: &str) {
+        if let Some(directory) = std::path::Path::new(&path).parent() {
+            fs::create_dir_all(directory).expect("Unable to create a directory");
+        }
+
+        let file = File::create(path).expect("Cannot create a file");
+
+        to_writer_pretty(&file, &self, PrettyConfig::default()).expect("Must save the game");
+    }
+
+    /// Returns the slot which has been saved the last time
+    pub fn latest_slot(game_mode: GameMode) -> Option<usize> {
+        (0..SAVE_SLOTS)
+            .filter_map(|slot| {
+                fs::metadata(Self::slot_path(slot, game_mode))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(|modified| (slot, modified))
+            })
+            .max_by_key(|(_, modified)| *modified)
+            .map(|(slot, _)| slot)
+    }
+}
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        // The game is saved and loaded from menus
+        //  that's why we have to handle it in any state
+        app.init_resource::<GameProgress>()
+            .insert_resource(CurrentSaveSlot(0))
+            .add_system(autosave)
+            .add_system(save_game)
+            .add_system(load_game)
+            .add_system(
+                apply_pending_save
+                    .run_in_state(ApplicationState::Game)
+                    .run_if_resource_exists::<PendingSave>(),
+            )
+            .add_event::<SaveGameEvent>()
+            .add_event::<LoadGameEvent>();
+    }
+}
+
+/// Saves the game into the current slot every time when the player pauses it
+fn autosave(
+    current_slot: Res<CurrentSaveSlot>,
+    mut pause_game_event: EventReader<PauseTheGameEvent>,
+    mut save_game_event: EventWriter<SaveGameEvent>,
+) {
+    if pause_game_event.iter().next().is_some() {
+        save_game_event.send(SaveGameEvent(current_slot.0));
+    }
+}
+
+// Allow too many arguments for specific function
+#[allow(clippy::too_many_arguments)]
+fn save_game(
+    game_mode: Res<GameMode>,
+    progress: Res<GameProgress>,
+    level_selection: Res<LevelSelection>,
+    ldtk_levels: Res<Assets<LdtkLevel>>,
+    mut current_slot: ResMut<CurrentSaveSlot>,
+    mut save_game_event: EventReader<SaveGameEvent>,
+    level_query: Query<&Handle<LdtkLevel>>,
+    player_query: Query<(&Transform, &Health, &Inventory, &Equipment), With<Player>>,
+) {
+    for event in save_game_event.iter() {
+        let (transform, health, inventory, equipment) = match player_query.get_single() {
+            Ok(player) => player,
+            Err(_) => continue,
+        };
+
+        let level_iid = level_query
+            .iter()
+            .filter_map(|level_handle| ldtk_levels.get(level_handle))
+            .find(|ldtk_level| level_selection.is_match(&0, &ldtk_level.level))
+            .map(|ldtk_level| ldtk_level.level.iid.clone());
+
+        // The level might be still loading. There is nothing to save yet
+        let level_iid = match level_iid {
+            Some(level_iid) => level_iid,
+            None => continue,
+        };
+
+        let save_data = SaveData {
+            level_iid,
+            position: (transform.translation.x, transform.translation.y),
+            health: health.clone(),
+            items: inventory.stacks.clone(),
+            equipment: [equipment.weapon, equipment.boots, equipment.armor]
+                .into_iter()
+                .flatten()
+                .collect(),
+            killed_enemies: progress.killed_enemies.iter().cloned().collect(),
+            opened_chests: progress.opened_chests.iter().cloned().collect(),
+        };
+
+        save_data.save(event.0, *game_mode);
+        current_slot.0 = event.0;
+    }
+}
+
+/// Re-creates the world as it has been saved. Enemies and chests are spawned
+///  by LDtk again to bring back everything what has been changed after the save.
+/// The player is kept because LDtk spawns it only on the start level
+// Allow too many arguments for specific function
+#[allow(clippy::too_many_arguments)]
+fn load_game(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut current_slot: ResMut<CurrentSaveSlot>,
+    mut load_game_event: EventReader<LoadGameEvent>,
+    mut resume_game_event: EventWriter<ResumeTheGameEvent>,
+    world_query: Query<(Entity, &Handle<LdtkAsset>)>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    let slot = match load_game_event.iter().last() {
+        Some(event) => event.0,
+        None => return,
+    };
+
+    let save_data = match SaveData::load(slot, *game_mode) {
+        Some(save_data) => save_data,
+        None => return,
+    };
+
+    // Spawn systems skip killed enemies and opened chests
+    commands.insert_resource(GameProgress {
+        killed_enemies: save_data.killed_enemies.iter().cloned().collect(),
+        opened_chests: save_data.opened_chests.iter().cloned().collect(),
+    });
+
+    let players: Vec<Entity> = player_query.iter().collect();
+
+    respawn_world(&mut commands, &world_query, &players);
+
+    *level_selection = LevelSelection::Iid(save_data.level_iid.clone());
+    current_slot.0 = slot;
+
+    commands.insert_resource(PendingSave(save_data));
+
+    resume_game_event.send(ResumeTheGameEvent);
+}
+
+/// Moves the player to the saved position and restores its items.
+/// Waits until `spawn_player` system inserts all components of the player
+#[allow(clippy::type_complexity)]
+fn apply_pending_save(
+    mut commands: Commands,
+    items_config: Res<ItemsConfig>,
+    pending_save: Res<PendingSave>,
+    mut player_query: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut Health,
+            &mut Inventory,
+            &mut Equipment,
+        ),
+        With<Player>,
+    >,
+) {
+    let (mut transform, mut velocity, mut health, mut inventory, mut equipment) =
+        match player_query.get_single_mut() {
+            Ok(player) => player,
+            Err(_) => return,
+        };
+
+    let save_data = &pending_save.0;
+
+    transform.translation.x = save_data.position.0;
+    transform.translation.y = save_data.position.1;
+    *velocity = Velocity::zero();
+
+    // Restarting the level brings the player back to the saved place
+    commands.insert_resource(LevelEntry {
+        level: LevelSelection::Iid(save_data.level_iid.clone()),
+        translation: transform.translation,
+    });
+
+    *health = save_data.health.clone();
+    inventory.stacks = save_data.items.clone();
+
+    *equipment = Equipment::default();
+    for item in save_data.equipment.iter() {
+        if let Some(equipment_config) = items_config.equipment(*item) {
+            equipment.equip(equipment_config.slot, *item, &items_config);
+        }
+    }
+
+    commands.remove_resource::<PendingSave>();
+}
+
+#[cfg(test)]
+mod save_tests {
+    use bevy::prelude::*;
+    use bevy_rapier2d::prelude::*;
+
+    use crate::common::Health;
+    use crate::items::{Equipment, Inventory, ItemStack, ItemType};
+    use crate::player::Player;
+    use crate::ron_parsers::ItemsConfig;
+
+    use super::{apply_pending_save, PendingSave, SaveData};
+
+    fn prepare_save_data() -> SaveData {
+        SaveData {
+            level_iid: String::from("saved-level"),
+            position: (120.0, -40.0),
+            health: Health { current: 3, max: 5 },
+            items: vec![ItemStack {
+                item: ItemType::Meat,
+                count: 2,
+            }],
+            equipment: Vec::new(),
+            killed_enemies: vec![String::from("enemy")],
+            opened_chests: vec![String::from("chest")],
+        }
+    }
+
+    fn save_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("dungeon_rogue_{}_{}.ron", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn should_load_what_has_been_saved() {
+        let path = save_path("round_trip");
+
+        prepare_save_data().save_to(&path);
+
+        let save_data = SaveData::load_from(&path).expect("The save must be loaded");
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(save_data.level_iid, "saved-level");
+        assert_eq!(save_data.position, (120.0, -40.0));
+        assert_eq!(save_data.health.current, 3);
+        assert_eq!(save_data.health.max, 5);
+        assert_eq!(save_data.items, prepare_save_data().items);
+        assert_eq!(save_data.killed_enemies, vec![String::from("enemy")]);
+        assert_eq!(save_data.opened_chests, vec![String::from("chest")]);
+    }
+
+    #[test]
+    fn should_ignore_corrupted_saves() {
+        let path = save_path("corrupted");
+
+        std::fs::write(&path, "SaveData(level_iid: ").unwrap();
+
+        let save_data = SaveData::load_from(&path);
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(save_data.is_none());
+        assert!(SaveData::load_from(&save_path("missing")).is_none());
+    }
+
+    #[test]
+    fn should_apply_the_save_when_the_player_is_ready() {
+        let mut app = App::new();
+
+        app.insert_resource(ItemsConfig {
+            effects: Vec::new(),
+            equipment: Vec::new(),
+        })
+        .insert_resource(PendingSave(prepare_save_data()))
+        .add_system(apply_pending_save);
+
+        // The player has been spawned by LDtk but the game isn't started yet
+        let player_entity = app
+            .world
+            .spawn()
+            .insert(Player)
+            .insert(Inventory::default())
+            .insert_bundle(TransformBundle::default())
+            .id();
+
+        app.update();
+
+        assert!(app.world.get_resource::<PendingSave>().is_some());
+
+        // `spawn_player` system inserts the rest of components
+        app.world
+            .entity_mut(player_entity)
+            .insert(Velocity::linear(Vec2::new(50.0, 0.0)))
+            .insert(Health { current: 5, max: 5 })
+            .insert(Equipment::default());
+
+        app.update();
+
+        assert!(app.world.get_resource::<PendingSave>().is_none());
+
+        let transform = app.world.get::<Transform>(player_entity).unwrap();
+        assert_eq!(transform.translation.truncate(), Vec2::new(120.0, -40.0));
+
+        assert_eq!(
+            app.world.get::<Velocity>(player_entity).unwrap().linvel,
+            Vec2::ZERO
+        );
+        assert_eq!(app.world.get::<Health>(player_entity).unwrap().current, 3);
+        assert_eq!(
+            app.world
+                .get::<Inventory>(player_entity)
+                .unwrap()
+                .count(ItemType::Meat),
+            2
+        );
+    }
+}
@@ -0,0 +1,20 @@
+#[cfg(test)]
+use bevy::prelude::*;
+
+#[cfg(test)]
+use crate::ron_parsers::{AttackerConfig, CombatConfig, InvulnerabilityConfig, StompConfig};
+
+#[cfg(test)]
+pub fn prepare_combat_config() -> CombatConfig {
+    CombatConfig {
+        player: AttackerConfig {
+            damage: 1,
+            knockback: Vec2::new(100.0, 30.0),
+        },
+        invulnerability: InvulnerabilityConfig {
+            duration: 1.0,
+            pass_through_enemies: true,
+        },
+        stomp: StompConfig { bounce: 50.0 },
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+use std::time::Duration;
+
+#[cfg(test)]
+use bevy::prelude::*;
+
+#[cfg(test)]
+use crate::enemy::{Patrol, PatrolMode};
+#[cfg(test)]
+use crate::ron_parsers::BehaviourConfig;
+
+#[cfg(test)]
+pub fn prepare_behaviour_config() -> BehaviourConfig {
+    BehaviourConfig {
+        sight_range: 100.0,
+        forget_time: 1.0,
+        chase_speed: 100.0,
+        attack_range: 20.0,
+        wind_up: 0.5,
+        attack_cooldown: 1.0,
+        flee_health: 0,
+    }
+}
+
+/// Patrol of the walking enemy along three points on the same line
+#[cfg(test)]
+pub fn prepare_patrol(mode: PatrolMode, waits: Vec<f32>) -> Patrol {
+    Patrol {
+        points: vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(50.0, 0.0),
+            Vec2::new(100.0, 0.0),
+        ],
+        index: 0,
+        mode,
+        forward: true,
+        waits,
+        wait: Duration::ZERO,
+        arrival_radius: 4.0,
+        flying: false,
+    }
+}
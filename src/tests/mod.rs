@@ -1 +1,4 @@
+pub mod combat;
+pub mod enemies;
+pub mod settings;
 pub mod sprites_textures;
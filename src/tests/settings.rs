@@ -0,0 +1,13 @@
+#[cfg(test)]
+use crate::ron_parsers::{Audio, Controls, Settings};
+
+#[cfg(test)]
+pub fn prepare_settings() -> Settings {
+    Settings {
+        audio: Audio {
+            state: false,
+            volume: 0,
+        },
+        controls: Controls::default(),
+    }
+}
@@ -1,83 +1,116 @@
 #[cfg(test)]
 use bevy::prelude::*;
 
+#[cfg(test)]
+use crate::common::SwingFrames;
+#[cfg(test)]
+use crate::enemy::EnemyType;
 #[cfg(test)]
 use crate::ron_parsers::{
-    EnemiesSprites, GameTextures, PlayerSprites, SpriteAssetInfo, TutorialSprites,
+    EnemiesSprites, EnemySprites, GameTextures, ItemsSprites, PlayerSprites, SpriteAssetInfo,
+    TutorialSprites,
 };
 
+#[cfg(test)]
+fn prepare_enemy_sprite() -> SpriteAssetInfo {
+    SpriteAssetInfo {
+        height: 10.0,
+        items: 4,
+        texture: Handle::default(),
+    }
+}
+
 #[cfg(test)]
 pub fn prepare_sprites() -> GameTextures {
     GameTextures {
         player: PlayerSprites {
             idle: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             run: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             climb: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             hurt: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             death: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             jump: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             double_jump: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             attack: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
             wall_slide: SpriteAssetInfo {
-                width: 10.0,
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
+            attack_swing: SwingFrames {
+                startup: 3,
+                active: 3,
+                recovery: 4,
+            },
         },
         enemies: EnemiesSprites {
-            gray: SpriteAssetInfo {
-                width: 10.0,
-                height: 10.0,
-                items: 10,
-                texture: Handle::default(),
-            },
-            long: SpriteAssetInfo {
-                width: 10.0,
+            sprites: ["Durt", "LongHair"]
+                .into_iter()
+                .map(|enemy_type| {
+                    (
+                        EnemyType(String::from(enemy_type)),
+                        EnemySprites {
+                            idle: prepare_enemy_sprite(),
+                            walk: prepare_enemy_sprite(),
+                            hurt: prepare_enemy_sprite(),
+                            attack: prepare_enemy_sprite(),
+                            death: prepare_enemy_sprite(),
+                        },
+                    )
+                })
+                .collect(),
+        },
+        items: ItemsSprites {
+            atlas: SpriteAssetInfo {
                 height: 10.0,
                 items: 10,
                 texture: Handle::default(),
             },
+            knife: 0,
+            healing_plant: 1,
+            meat: 2,
+            boots: 3,
+            water: 4,
+            gem: 5,
+            scraps: 6,
+            battery: 7,
+            food: 8,
+            medkit: 9,
+            power_orb: 10,
+            key: 11,
+            chest: 12,
         },
         tutorials: TutorialSprites {
             movement: Handle::default(),
@@ -4,6 +4,9 @@ pub const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
 pub const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
 pub const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
 
+/// The button which is selected by the keyboard or the gamepad (or hovered by the mouse)
+pub const FOCUSED_BUTTON: Color = Color::rgb(0.3, 0.3, 0.5);
+
 pub fn build_classic_button() -> ButtonBundle {
     ButtonBundle {
         style: Style {
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
 use iyes_loopless::prelude::*;
 
-use crate::{ApplicationState, ApplicationStateMenu, ExitTheGameEvent};
+use crate::{
+    checkpoint::RespawnPlayerEvent, ApplicationState, ApplicationStateMenu, ExitTheGameEvent,
+    RestartLevelEvent,
+};
 
 use super::components::{
     build_classic_button, build_classic_text, ClassicButtonTextParams, HOVERED_BUTTON,
@@ -11,6 +14,7 @@ use super::components::{
 pub struct DeadMenuUIPlugin;
 
 enum DeadButtonType {
+    Respawn,
     Replay,
     Exit,
 }
@@ -92,6 +96,27 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                             color: Color::NONE.into(),
                             ..Default::default()
                         })
+                        .with_children(|parent| {
+                            let button = build_classic_button();
+
+                            parent
+                                .spawn_bundle(ButtonBundle {
+                                    // The text is longer than in the other buttons
+                                    style: Style {
+                                        size: Size::new(Val::Px(250.0), Val::Px(50.0)),
+                                        ..button.style
+                                    },
+                                    ..button
+                                })
+                                .insert(MenuButton(DeadButtonType::Respawn))
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Respawn at checkpoint",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        })
                         .with_children(|parent| {
                             parent
                                 .spawn_bundle(build_classic_button())
@@ -133,6 +158,8 @@ fn button_interaction(
         (&Interaction, &mut UiColor, Option<&MenuButton>),
         (Changed<Interaction>, With<Button>),
     >,
+    mut respawn_player_event: EventWriter<RespawnPlayerEvent>,
+    mut restart_level_event: EventWriter<RestartLevelEvent>,
     mut exit_game_event: EventWriter<ExitTheGameEvent>,
 ) {
     for (interaction, mut color, button) in interaction_query.iter_mut() {
@@ -142,8 +169,11 @@ fn button_interaction(
 
                 if let Some(button) = button {
                     match button.0 {
+                        DeadButtonType::Respawn => {
+                            respawn_player_event.send(RespawnPlayerEvent);
+                        }
                         DeadButtonType::Replay => {
-                            // We should start the game from the begining
+                            restart_level_event.send(RestartLevelEvent);
                         }
                         DeadButtonType::Exit => {
                             exit_game_event.send(ExitTheGameEvent);
@@ -0,0 +1,274 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    items::{Equipment, EquipmentSlot, Inventory, ItemType, UnequipItemEvent, UseItemEvent},
+    player::Player,
+    ApplicationState, ApplicationStateMenu, ResumeTheGameEvent,
+};
+
+use super::components::{
+    build_classic_button, build_classic_text, ClassicButtonTextParams, HOVERED_BUTTON,
+    NORMAL_BUTTON, PRESSED_BUTTON,
+};
+use super::navigation::BackButton;
+
+pub struct InventoryMenuUIPlugin;
+
+impl Plugin for InventoryMenuUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Menu(ApplicationStateMenu::Inventory))
+                .with_system(button_interaction)
+                .with_system(update_inventory_list)
+                .into(),
+        )
+        .add_enter_system(
+            ApplicationState::Menu(ApplicationStateMenu::Inventory),
+            setup,
+        )
+        .add_exit_system(
+            ApplicationState::Menu(ApplicationStateMenu::Inventory),
+            destroy,
+        );
+    }
+}
+
+#[derive(Component)]
+struct InventoryMenuUI;
+
+/// Exists while the inventory is opened from the game (not from the main menu).
+/// The back button returns the player to the game in this case
+pub struct InventoryOpenedInGame;
+
+/// Container which contains all item stacks of the player
+#[derive(Component)]
+struct InventoryList;
+
+enum InventoryButtonType {
+    UseItem(ItemType),
+    Unequip(EquipmentSlot),
+    BackToMenu,
+}
+
+#[derive(Component)]
+struct InventoryButton(InventoryButtonType);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        // Top-level container which contains the whole page
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(50.0), Val::Percent(95.0)),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::SpaceBetween,
+                        border: UiRect::all(Val::Px(5.0)),
+                        align_content: AlignContent::Center,
+                        ..Default::default()
+                    },
+                    color: Color::rgba(0.2, 0.2, 0.2, 0.5).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(20.0)),
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                ..Default::default()
+                            },
+                            color: Color::rgba(0.2, 0.2, 0.2, 0.1).into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn_bundle(build_classic_text(
+                                "Inventory",
+                                &asset_server,
+                                Some(ClassicButtonTextParams { font_size: 30.0 }),
+                            ));
+                        });
+                })
+                .with_children(|parent| {
+                    // Items are rendered by `update_inventory_list` system
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(60.0)),
+                                flex_direction: FlexDirection::ColumnReverse,
+                                border: UiRect::all(Val::Px(5.0)),
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .insert(InventoryList);
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(20.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(build_classic_button())
+                                .insert(InventoryButton(InventoryButtonType::BackToMenu))
+                                .insert(BackButton)
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Back",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        });
+                });
+        })
+        .insert(InventoryMenuUI);
+}
+
+fn destroy(mut commands: Commands, inventory_menu_ui_query: Query<Entity, With<InventoryMenuUI>>) {
+    let inventory_menu_entity = inventory_menu_ui_query.single();
+
+    commands.entity(inventory_menu_entity).despawn_recursive();
+    commands.remove_resource::<InventoryOpenedInGame>();
+}
+
+/// Re-renders the list of items when the menu has just been opened
+///  or the player inventory has been changed (e.g. the item has been used)
+#[allow(clippy::type_complexity)]
+fn update_inventory_list(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    list_query: Query<(Entity, ChangeTrackers<InventoryList>)>,
+    player_query: Query<
+        (
+            &Inventory,
+            &Equipment,
+            ChangeTrackers<Inventory>,
+            ChangeTrackers<Equipment>,
+        ),
+        With<Player>,
+    >,
+) {
+    if let (
+        Ok((list_entity, list_tracker)),
+        Ok((inventory, equipment, inventory_tracker, equipment_tracker)),
+    ) = (list_query.get_single(), player_query.get_single())
+    {
+        if !list_tracker.is_added()
+            && !inventory_tracker.is_changed()
+            && !equipment_tracker.is_changed()
+        {
+            return;
+        }
+
+        commands.entity(list_entity).despawn_descendants();
+
+        commands.entity(list_entity).with_children(|parent| {
+            // Equipped items are shown first. Click on them takes them off
+            for slot in [
+                EquipmentSlot::Weapon,
+                EquipmentSlot::Boots,
+                EquipmentSlot::Armor,
+            ] {
+                if let Some(item) = equipment.slot(slot) {
+                    parent
+                        .spawn_bundle(build_classic_button())
+                        .insert(InventoryButton(InventoryButtonType::Unequip(slot)))
+                        .with_children(|parent| {
+                            parent.spawn_bundle(build_classic_text(
+                                format!("{} (on)", item.name()).as_str(),
+                                &asset_server,
+                                None,
+                            ));
+                        });
+                }
+            }
+
+            if inventory.stacks.is_empty() {
+                parent.spawn_bundle(build_classic_text("Empty", &asset_server, None));
+
+                return;
+            }
+
+            for stack in inventory.stacks.iter() {
+                parent
+                    .spawn_bundle(build_classic_button())
+                    .insert(InventoryButton(InventoryButtonType::UseItem(stack.item)))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(build_classic_text(
+                            format!("{} x{}", stack.item.name(), stack.count).as_str(),
+                            &asset_server,
+                            None,
+                        ));
+                    });
+            }
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn button_interaction(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &mut UiColor, &InventoryButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    opened_in_game: Option<Res<InventoryOpenedInGame>>,
+    mut use_item_event: EventWriter<UseItemEvent>,
+    mut unequip_item_event: EventWriter<UnequipItemEvent>,
+    mut resume_game_event: EventWriter<ResumeTheGameEvent>,
+) {
+    for (interaction, mut color, inventory_button) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = UiColor(PRESSED_BUTTON);
+
+                match inventory_button.0 {
+                    InventoryButtonType::UseItem(item) => {
+                        use_item_event.send(UseItemEvent(item));
+                    }
+                    InventoryButtonType::Unequip(slot) => {
+                        unequip_item_event.send(UnequipItemEvent(slot));
+                    }
+                    InventoryButtonType::BackToMenu => {
+                        if opened_in_game.is_some() {
+                            resume_game_event.send(ResumeTheGameEvent);
+                        } else {
+                            commands.insert_resource(NextState(ApplicationState::Menu(
+                                ApplicationStateMenu::Main,
+                            )));
+                        }
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = UiColor(HOVERED_BUTTON);
+            }
+            Interaction::None => {
+                *color = UiColor(NORMAL_BUTTON);
+            }
+        }
+    }
+}
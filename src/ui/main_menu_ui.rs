@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 use iyes_loopless::prelude::*;
 
-use crate::{ApplicationState, ApplicationStateMenu, ExitTheGameEvent, ResumeTheGameEvent};
+use crate::{
+    save::{LoadGameEvent, SaveData},
+    ApplicationState, ApplicationStateMenu, ExitTheGameEvent, GameMode, RestartLevelEvent,
+    ResumeTheGameEvent,
+};
 
 use super::components::{
     build_classic_button, build_classic_text, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON,
@@ -26,7 +30,11 @@ impl Plugin for MainMenuUIPlugin {
 struct MainMenuUI;
 
 enum MenuButtonType {
+    Continue,
     Play,
+    LoadGame,
+    RestartLevel,
+    Inventory,
     Settings,
     Exit,
 }
@@ -94,6 +102,18 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                             color: Color::NONE.into(),
                             ..Default::default()
                         })
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(build_classic_button())
+                                .insert(MenuButton(MenuButtonType::Continue))
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Continue",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        })
                         .with_children(|parent| {
                             parent
                                 .spawn_bundle(build_classic_button())
@@ -106,6 +126,42 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                                     ));
                                 });
                         })
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(build_classic_button())
+                                .insert(MenuButton(MenuButtonType::LoadGame))
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Load game",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(build_classic_button())
+                                .insert(MenuButton(MenuButtonType::RestartLevel))
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Restart",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(build_classic_button())
+                                .insert(MenuButton(MenuButtonType::Inventory))
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Inventory",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        })
                         .with_children(|parent| {
                             parent
                                 .spawn_bundle(build_classic_button())
@@ -144,11 +200,14 @@ fn destroy(mut commands: Commands, main_menu_ui_query: Query<Entity, With<MainMe
 #[allow(clippy::type_complexity)]
 fn button_interaction(
     mut commands: Commands,
+    game_mode: Res<GameMode>,
     mut interaction_query: Query<
         (&Interaction, &mut UiColor, Option<&MenuButton>),
         (Changed<Interaction>, With<Button>),
     >,
     mut resume_game_event: EventWriter<ResumeTheGameEvent>,
+    mut load_game_event: EventWriter<LoadGameEvent>,
+    mut restart_level_event: EventWriter<RestartLevelEvent>,
     mut exit_game_event: EventWriter<ExitTheGameEvent>,
 ) {
     for (interaction, mut color, menu_button) in interaction_query.iter_mut() {
@@ -157,12 +216,31 @@ fn button_interaction(
                 *color = UiColor(PRESSED_BUTTON);
                 if let Some(menu_button) = menu_button {
                     match menu_button.0 {
+                        MenuButtonType::Continue => {
+                            // Continue the last saved game if there is any
+                            if let Some(slot) = SaveData::latest_slot(*game_mode) {
+                                load_game_event.send(LoadGameEvent(slot));
+                            }
+                        }
                         MenuButtonType::Play => {
                             resume_game_event.send(ResumeTheGameEvent);
                         }
+                        MenuButtonType::LoadGame => {
+                            commands.insert_resource(NextState(ApplicationState::Menu(
+                                ApplicationStateMenu::SaveSlots,
+                            )));
+                        }
+                        MenuButtonType::RestartLevel => {
+                            restart_level_event.send(RestartLevelEvent);
+                        }
                         MenuButtonType::Exit => {
                             exit_game_event.send(ExitTheGameEvent);
                         }
+                        MenuButtonType::Inventory => {
+                            commands.insert_resource(NextState(ApplicationState::Menu(
+                                ApplicationStateMenu::Inventory,
+                            )));
+                        }
                         MenuButtonType::Settings => {
                             commands.insert_resource(NextState(ApplicationState::Menu(
                                 ApplicationStateMenu::Settings,
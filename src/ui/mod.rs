@@ -2,9 +2,13 @@ use bevy::prelude::*;
 
 mod components;
 mod dead_menu_ui;
+mod inventory_menu_ui;
 mod main_menu_ui;
+mod navigation;
+mod save_slots_menu_ui;
 mod settings_menu_ui;
 
+pub use inventory_menu_ui::InventoryOpenedInGame;
 pub use settings_menu_ui::SettingsButton;
 
 pub struct UIPlugin;
@@ -13,6 +17,9 @@ impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(main_menu_ui::MainMenuUIPlugin)
             .add_plugin(dead_menu_ui::DeadMenuUIPlugin)
+            .add_plugin(inventory_menu_ui::InventoryMenuUIPlugin)
+            .add_plugin(navigation::MenuNavigationPlugin)
+            .add_plugin(save_slots_menu_ui::SaveSlotsMenuUIPlugin)
             .add_plugin(settings_menu_ui::SettingsMenuUIPlugin);
     }
 }
@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{actions::Action, ApplicationState};
+
+use super::components::{FOCUSED_BUTTON, NORMAL_BUTTON};
+
+pub struct MenuNavigationPlugin;
+
+/// Button which is selected by the keyboard or the gamepad
+#[derive(Component)]
+pub struct Focused;
+
+/// Button which is pressed when the player goes back from the menu
+#[derive(Component)]
+pub struct BackButton;
+
+/// Buttons which are further than this distance from the movement line
+///  are less preferable than the closer ones
+const CROSS_DISTANCE_FACTOR: f32 = 2.0;
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_not_in_state(ApplicationState::Game)
+                .with_system(focus_first_button)
+                .with_system(focus_hovered_button)
+                .with_system(move_focus)
+                .with_system(press_menu_button)
+                .into(),
+        )
+        // Menu systems change colors of buttons on interaction
+        //  that's why the focus has to be styled after them
+        .add_system_to_stage(CoreStage::PostUpdate, style_focused_button)
+        .add_system_to_stage(CoreStage::PostUpdate, style_unfocused_button);
+    }
+}
+
+/// Focuses the first (top left) button when the menu is opened
+///  or the focused button has been removed
+fn focus_first_button(
+    mut commands: Commands,
+    buttons_query: Query<(Entity, &GlobalTransform), With<Button>>,
+    focused_query: Query<With<Focused>>,
+) {
+    if !focused_query.is_empty() {
+        return;
+    }
+
+    let first_button = buttons_query.iter().max_by(|(_, a), (_, b)| {
+        let (a, b) = (a.translation(), b.translation());
+
+        // UI `y` axis goes from the bottom to the top
+        a.y.partial_cmp(&b.y)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.x.partial_cmp(&a.x).unwrap_or(std::cmp::Ordering::Equal))
+    });
+
+    if let Some((entity, _)) = first_button {
+        commands.entity(entity).insert(Focused);
+    }
+}
+
+/// The mouse and the keyboard share the same focus
+fn focus_hovered_button(
+    mut commands: Commands,
+    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<Button>)>,
+    focused_query: Query<Entity, With<Focused>>,
+) {
+    for (entity, interaction) in interaction_query.iter() {
+        if *interaction != Interaction::Hovered || focused_query.get(entity).is_ok() {
+            continue;
+        }
+
+        for focused_entity in focused_query.iter() {
+            commands.entity(focused_entity).remove::<Focused>();
+        }
+
+        commands.entity(entity).insert(Focused);
+    }
+}
+
+/// Moves the focus to the closest button in the direction of the action
+fn move_focus(
+    mut commands: Commands,
+    actions: Res<Input<Action>>,
+    buttons_query: Query<(Entity, &GlobalTransform), With<Button>>,
+    focused_query: Query<(Entity, &GlobalTransform), (With<Focused>, With<Button>)>,
+) {
+    // UI `y` axis goes from the bottom to the top
+    let direction = if actions.just_pressed(Action::MenuUp) {
+        Vec2::Y
+    } else if actions.just_pressed(Action::MenuDown) {
+        Vec2::NEG_Y
+    } else if actions.just_pressed(Action::MenuLeft) {
+        Vec2::NEG_X
+    } else if actions.just_pressed(Action::MenuRight) {
+        Vec2::X
+    } else {
+        return;
+    };
+
+    let (focused_entity, focused_transform) = match focused_query.get_single() {
+        Ok(focused) => focused,
+        Err(_) => return,
+    };
+
+    let focused_position = focused_transform.translation().truncate();
+
+    let next_button = buttons_query
+        .iter()
+        .filter_map(|(entity, transform)| {
+            let offset = transform.translation().truncate() - focused_position;
+            let distance = offset.dot(direction);
+
+            if distance <= 0.0 {
+                return None;
+            }
+
+            let cross_distance = (offset - direction * distance).length();
+
+            Some((entity, distance + cross_distance * CROSS_DISTANCE_FACTOR))
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    if let Some((entity, _)) = next_button {
+        commands.entity(focused_entity).remove::<Focused>();
+        commands.entity(entity).insert(Focused);
+    }
+}
+
+/// Presses the focused button (or the back button) as it has been
+///  clicked by the mouse. The button is released on the next frame
+fn press_menu_button(
+    actions: Res<Input<Action>>,
+    mut pressed_button: Local<Option<Entity>>,
+    mut interaction_query: Query<&mut Interaction, With<Button>>,
+    focused_query: Query<Entity, (With<Focused>, With<Button>)>,
+    back_button_query: Query<Entity, (With<BackButton>, With<Button>)>,
+) {
+    if let Some(entity) = pressed_button.take() {
+        // The button might be removed with the whole menu
+        if let Ok(mut interaction) = interaction_query.get_mut(entity) {
+            *interaction = Interaction::None;
+        }
+    }
+
+    let button = if actions.just_pressed(Action::MenuSelect) {
+        focused_query.get_single().ok()
+    } else if actions.just_pressed(Action::MenuBack) {
+        back_button_query.get_single().ok()
+    } else {
+        None
+    };
+
+    if let Some(entity) = button {
+        if let Ok(mut interaction) = interaction_query.get_mut(entity) {
+            *interaction = Interaction::Clicked;
+            *pressed_button = Some(entity);
+        }
+    }
+}
+
+fn style_focused_button(mut focused_query: Query<&mut UiColor, (With<Focused>, With<Button>)>) {
+    for mut color in focused_query.iter_mut() {
+        if color.0 != FOCUSED_BUTTON {
+            *color = UiColor(FOCUSED_BUTTON);
+        }
+    }
+}
+
+fn style_unfocused_button(
+    removed_focus: RemovedComponents<Focused>,
+    mut color_query: Query<&mut UiColor, (Without<Focused>, With<Button>)>,
+) {
+    for entity in removed_focus.iter() {
+        // Buttons with their own state (e.g. active settings)
+        //  restore the color by themselves
+        if let Ok(mut color) = color_query.get_mut(entity) {
+            *color = UiColor(NORMAL_BUTTON);
+        }
+    }
+}
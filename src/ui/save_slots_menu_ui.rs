@@ -0,0 +1,272 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::*;
+
+use crate::{
+    save::{LoadGameEvent, SaveData, SaveGameEvent, SAVE_SLOTS},
+    ApplicationState, ApplicationStateMenu, GameMode,
+};
+
+use super::components::{
+    build_classic_button, build_classic_text, ClassicButtonTextParams, HOVERED_BUTTON,
+    NORMAL_BUTTON, PRESSED_BUTTON,
+};
+use super::navigation::BackButton;
+
+pub struct SaveSlotsMenuUIPlugin;
+
+impl Plugin for SaveSlotsMenuUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Menu(ApplicationStateMenu::SaveSlots))
+                .with_system(button_interaction)
+                .with_system(update_save_slots_list)
+                .into(),
+        )
+        .add_enter_system(
+            ApplicationState::Menu(ApplicationStateMenu::SaveSlots),
+            setup,
+        )
+        .add_exit_system(
+            ApplicationState::Menu(ApplicationStateMenu::SaveSlots),
+            destroy,
+        );
+    }
+}
+
+#[derive(Component)]
+struct SaveSlotsMenuUI;
+
+/// Container which contains all save slots
+#[derive(Component)]
+struct SaveSlotsList;
+
+enum SaveSlotsButtonType {
+    Save(usize),
+    Load(usize),
+    BackToMenu,
+}
+
+#[derive(Component)]
+struct SaveSlotsButton(SaveSlotsButtonType);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        // Top-level container which contains the whole page
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(50.0), Val::Percent(95.0)),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::SpaceBetween,
+                        border: UiRect::all(Val::Px(5.0)),
+                        align_content: AlignContent::Center,
+                        ..Default::default()
+                    },
+                    color: Color::rgba(0.2, 0.2, 0.2, 0.5).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(20.0)),
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                ..Default::default()
+                            },
+                            color: Color::rgba(0.2, 0.2, 0.2, 0.1).into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn_bundle(build_classic_text(
+                                "Save / Load",
+                                &asset_server,
+                                Some(ClassicButtonTextParams { font_size: 30.0 }),
+                            ));
+                        });
+                })
+                .with_children(|parent| {
+                    // Slots are rendered by `update_save_slots_list` system
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(60.0)),
+                                flex_direction: FlexDirection::ColumnReverse,
+                                border: UiRect::all(Val::Px(5.0)),
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .insert(SaveSlotsList);
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(20.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(build_classic_button())
+                                .insert(SaveSlotsButton(SaveSlotsButtonType::BackToMenu))
+                                .insert(BackButton)
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Back",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        });
+                });
+        })
+        .insert(SaveSlotsMenuUI);
+}
+
+fn destroy(mut commands: Commands, save_slots_menu_ui_query: Query<Entity, With<SaveSlotsMenuUI>>) {
+    let save_slots_menu_entity = save_slots_menu_ui_query.single();
+
+    commands.entity(save_slots_menu_entity).despawn_recursive();
+}
+
+/// Re-renders the list of slots when the menu has just been opened
+///  or the game has been saved.
+/// The game is saved by another system that's why we wait
+///  for the next frame to read the updated file
+fn update_save_slots_list(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_mode: Res<GameMode>,
+    mut save_game_event: EventReader<SaveGameEvent>,
+    mut should_update: Local<bool>,
+    list_query: Query<(Entity, ChangeTrackers<SaveSlotsList>)>,
+) {
+    if let Ok((list_entity, list_tracker)) = list_query.get_single() {
+        if save_game_event.iter().next().is_some() {
+            *should_update = true;
+
+            return;
+        }
+
+        if !list_tracker.is_added() && !*should_update {
+            return;
+        }
+
+        *should_update = false;
+
+        commands.entity(list_entity).despawn_descendants();
+
+        commands.entity(list_entity).with_children(|parent| {
+            for slot in 0..SAVE_SLOTS {
+                let description = match SaveData::load(slot, *game_mode) {
+                    Some(save_data) => format!(
+                        "Slot {}: HP {}/{}",
+                        slot + 1,
+                        save_data.health.current,
+                        save_data.health.max
+                    ),
+                    None => format!("Slot {}: Empty", slot + 1),
+                };
+
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(100.0), Val::Auto),
+                            justify_content: JustifyContent::SpaceBetween,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn_bundle(build_classic_text(
+                            description.as_str(),
+                            &asset_server,
+                            None,
+                        ));
+
+                        parent
+                            .spawn_bundle(build_classic_button())
+                            .insert(SaveSlotsButton(SaveSlotsButtonType::Save(slot)))
+                            .with_children(|parent| {
+                                parent.spawn_bundle(build_classic_text(
+                                    "Save",
+                                    &asset_server,
+                                    None,
+                                ));
+                            });
+
+                        parent
+                            .spawn_bundle(build_classic_button())
+                            .insert(SaveSlotsButton(SaveSlotsButtonType::Load(slot)))
+                            .with_children(|parent| {
+                                parent.spawn_bundle(build_classic_text(
+                                    "Load",
+                                    &asset_server,
+                                    None,
+                                ));
+                            });
+                    });
+            }
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn button_interaction(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &mut UiColor, &SaveSlotsButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut save_game_event: EventWriter<SaveGameEvent>,
+    mut load_game_event: EventWriter<LoadGameEvent>,
+) {
+    for (interaction, mut color, save_slots_button) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = UiColor(PRESSED_BUTTON);
+
+                match save_slots_button.0 {
+                    SaveSlotsButtonType::Save(slot) => {
+                        save_game_event.send(SaveGameEvent(slot));
+                    }
+                    SaveSlotsButtonType::Load(slot) => {
+                        load_game_event.send(LoadGameEvent(slot));
+                    }
+                    SaveSlotsButtonType::BackToMenu => {
+                        commands.insert_resource(NextState(ApplicationState::Menu(
+                            ApplicationStateMenu::Main,
+                        )));
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = UiColor(HOVERED_BUTTON);
+            }
+            Interaction::None => {
+                *color = UiColor(NORMAL_BUTTON);
+            }
+        }
+    }
+}
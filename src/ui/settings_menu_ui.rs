@@ -2,11 +2,17 @@ use bevy::prelude::*;
 use bevy_inspector_egui::Inspectable;
 use iyes_loopless::prelude::*;
 
-use crate::{audio::AudioState, ApplicationState, ApplicationStateMenu};
+use crate::{
+    actions::{key_name, Action, ActionRebinding},
+    audio::AudioState,
+    ron_parsers::Settings,
+    ApplicationState, ApplicationStateMenu,
+};
 
 use super::components::{
     build_classic_button, build_classic_text, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON,
 };
+use super::navigation::BackButton;
 
 pub struct SettingsMenuUIPlugin;
 
@@ -29,6 +35,21 @@ impl Plugin for SettingsMenuUIPlugin {
             ApplicationState::Menu(ApplicationStateMenu::Settings),
             destroy,
         )
+        .add_system_set(
+            ConditionSet::new()
+                .run_in_state(ApplicationState::Menu(ApplicationStateMenu::Controls))
+                .with_system(controls_button_interaction)
+                .with_system(rebind_action)
+                .into(),
+        )
+        .add_enter_system(
+            ApplicationState::Menu(ApplicationStateMenu::Controls),
+            setup_controls,
+        )
+        .add_exit_system(
+            ApplicationState::Menu(ApplicationStateMenu::Controls),
+            destroy_controls,
+        )
         .add_event::<ChangeMusicStateEvent>()
         .add_event::<ChangeMusicVolumeEvent>();
     }
@@ -53,6 +74,7 @@ impl Default for MusicState {
 enum SettingsButtonType {
     MusicStatus(MusicState),
     MusicVolume(i8),
+    Controls,
     BackToMenu,
 }
 
@@ -102,6 +124,11 @@ fn button_interaction(
                 SettingsButtonType::MusicVolume(volume) => {
                     change_music_volume_event.send(ChangeMusicVolumeEvent(*volume));
                 }
+                SettingsButtonType::Controls => {
+                    commands.insert_resource(NextState(ApplicationState::Menu(
+                        ApplicationStateMenu::Controls,
+                    )));
+                }
                 SettingsButtonType::BackToMenu => {
                     commands.insert_resource(NextState(ApplicationState::Menu(
                         ApplicationStateMenu::Main,
@@ -383,10 +410,23 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, audio_state: Re
                             color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
                             ..Default::default()
                         })
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(build_classic_button())
+                                .insert(SettingsButton(SettingsButtonType::Controls))
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(build_classic_text(
+                                        "Controls",
+                                        &asset_server,
+                                        None,
+                                    ));
+                                });
+                        })
                         .with_children(|parent| {
                             parent
                                 .spawn_bundle(build_classic_button())
                                 .insert(SettingsButton(SettingsButtonType::BackToMenu))
+                                .insert(BackButton)
                                 .with_children(|parent| {
                                     parent.spawn_bundle(build_classic_text(
                                         "Back",
@@ -405,3 +445,200 @@ fn destroy(mut commands: Commands, settings_menu_ui_query: Query<Entity, With<Se
 
     commands.entity(settings_menu_ui).despawn_recursive();
 }
+
+#[derive(Component)]
+struct ControlsMenuUI;
+
+enum ControlsButtonType {
+    Rebind(Action),
+    BackToSettings,
+}
+
+#[derive(Component)]
+struct ControlsButton(ControlsButtonType);
+
+/// Text which shows the key bound to the action
+#[derive(Component)]
+struct BindingText(Action);
+
+fn setup_controls(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<Settings>) {
+    commands
+        // Top-level container which contains the whole page
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(20.0), Val::Percent(20.0)),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..Default::default()
+                    },
+                    color: Color::rgba(0.2, 0.2, 0.2, 0.1).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(build_classic_text("Controls", &asset_server, None));
+                });
+        })
+        .with_children(|parent| {
+            // Right panel
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(45.0), Val::Percent(100.0)),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        justify_content: JustifyContent::SpaceEvenly,
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(5.0)),
+                        ..Default::default()
+                    },
+                    color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for action in Action::REBINDABLE {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    size: Size::new(Val::Percent(100.0), Val::Px(45.0)),
+                                    justify_content: JustifyContent::SpaceBetween,
+                                    align_items: AlignItems::Center,
+                                    padding: UiRect::new(
+                                        Val::Px(30.0),
+                                        Val::Px(30.0),
+                                        Val::Px(0.0),
+                                        Val::Px(0.0),
+                                    ),
+                                    ..Default::default()
+                                },
+                                color: Color::NONE.into(),
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn_bundle(build_classic_text(
+                                    action.name(),
+                                    &asset_server,
+                                    None,
+                                ));
+
+                                parent
+                                    .spawn_bundle(build_classic_button())
+                                    .insert(ControlsButton(ControlsButtonType::Rebind(action)))
+                                    .with_children(|parent| {
+                                        parent
+                                            .spawn_bundle(build_classic_text(
+                                                &key_name(settings.controls.key(action)),
+                                                &asset_server,
+                                                None,
+                                            ))
+                                            .insert(BindingText(action));
+                                    });
+                            });
+                    }
+
+                    parent
+                        .spawn_bundle(build_classic_button())
+                        .insert(ControlsButton(ControlsButtonType::BackToSettings))
+                        .insert(BackButton)
+                        .with_children(|parent| {
+                            parent.spawn_bundle(build_classic_text("Back", &asset_server, None));
+                        });
+                });
+        })
+        .insert(ControlsMenuUI);
+}
+
+fn destroy_controls(
+    mut commands: Commands,
+    controls_menu_ui_query: Query<Entity, With<ControlsMenuUI>>,
+) {
+    let controls_menu_ui = controls_menu_ui_query.single();
+
+    commands.entity(controls_menu_ui).despawn_recursive();
+
+    // Leave the page without changing the binding
+    commands.remove_resource::<ActionRebinding>();
+}
+
+#[allow(clippy::type_complexity)]
+fn controls_button_interaction(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut interaction_query: Query<
+        (&Interaction, &mut UiColor, &ControlsButton),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut binding_text_query: Query<(&mut Text, &BindingText)>,
+) {
+    for (interaction, mut color, controls_button) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = UiColor(PRESSED_BUTTON);
+
+                match controls_button.0 {
+                    ControlsButtonType::Rebind(action) => {
+                        commands.insert_resource(ActionRebinding(action));
+
+                        // Only one action may be rebound at the same time
+                        for (mut text, binding_text) in binding_text_query.iter_mut() {
+                            text.sections[0].value = if binding_text.0 == action {
+                                String::from("Press a key")
+                            } else {
+                                key_name(settings.controls.key(binding_text.0))
+                            };
+                        }
+                    }
+                    ControlsButtonType::BackToSettings => {
+                        commands.insert_resource(NextState(ApplicationState::Menu(
+                            ApplicationStateMenu::Settings,
+                        )));
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = UiColor(HOVERED_BUTTON);
+            }
+            Interaction::None => {
+                *color = UiColor(NORMAL_BUTTON);
+            }
+        }
+    }
+}
+
+/// Binds the key to the action which is being rebound.
+/// The key is taken on release. Otherwise the new action
+///  would be triggered right after rebinding
+fn rebind_action(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    rebinding: Option<Res<ActionRebinding>>,
+    mut settings: ResMut<Settings>,
+    mut binding_text_query: Query<(&mut Text, &BindingText)>,
+) {
+    let rebinding = match rebinding {
+        Some(rebinding) => rebinding,
+        None => return,
+    };
+
+    if let Some(key) = keyboard.get_just_released().next() {
+        settings.controls.bindings.insert(rebinding.0, *key);
+
+        for (mut text, binding_text) in binding_text_query.iter_mut() {
+            if binding_text.0 == rebinding.0 {
+                text.sections[0].value = key_name(Some(*key));
+            }
+        }
+
+        commands.remove_resource::<ActionRebinding>();
+    }
+}